@@ -1,6 +1,25 @@
 extern crate pkg_config;
+#[cfg(feature = "bundled")]
+extern crate cmake;
+
+use std::env;
 
 fn main() {
+    // NOTE(erick): With the "dynamic" feature the library is loaded at
+    // runtime, so there is nothing to link against.
+    if env::var_os("CARGO_FEATURE_DYNAMIC").is_some() {
+        return;
+    }
+
+    #[cfg(feature = "bundled")]
+    build_bundled();
+
+    #[cfg(not(feature = "bundled"))]
+    link_system();
+}
+
+#[cfg(not(feature = "bundled"))]
+fn link_system() {
     let lib_mediainfo = pkg_config::probe_library("libmediainfo");
     if lib_mediainfo.is_err() {
         panic!("Could not find MediaInfo via pkgconfig");
@@ -8,3 +27,51 @@ fn main() {
         // panic!("{:?}", lib_mediainfo);
     }
 }
+
+// NOTE(erick): Builds MediaInfoLib (and ZenLib, through its BUILD_ZENLIB
+// option) from the vendor/ submodules and links them statically. zlib
+// comes from the libz-sys crate, built from source as well.
+#[cfg(feature = "bundled")]
+fn build_bundled() {
+    use std::path::Path;
+
+    let manifest_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let vendor_dir = Path::new(&manifest_dir).join("vendor");
+    if !vendor_dir.join("MediaInfoLib").join("Project").join("CMake").exists() ||
+       !vendor_dir.join("ZenLib").join("Project").join("CMake").exists() {
+        panic!("Could not find the vendored MediaInfoLib/ZenLib sources. \
+                Run 'git submodule update --init vendor/MediaInfoLib vendor/ZenLib'");
+    }
+
+    let mut config = cmake::Config::new(vendor_dir.join("MediaInfoLib").join("Project").join("CMake"));
+    config.define("BUILD_SHARED_LIBS", "OFF")
+          .define("BUILD_ZENLIB", "ON")
+          .define("BUILD_ZLIB", "OFF")
+          .define("CMAKE_DISABLE_FIND_PACKAGE_CURL", "ON")
+          .define("CMAKE_POSITION_INDEPENDENT_CODE", "ON");
+
+    if let (Some(include), Some(root)) = (env::var_os("DEP_Z_INCLUDE"), env::var_os("DEP_Z_ROOT")) {
+        let root = Path::new(&root);
+        let library = if cfg!(windows) { root.join("lib").join("z.lib") } else { root.join("lib").join("libz.a") };
+        config.define("ZLIB_INCLUDE_DIR", include)
+              .define("ZLIB_LIBRARY", library);
+    }
+
+    let install_dir = config.build();
+
+    println!("cargo:rerun-if-changed=vendor/MediaInfoLib");
+    println!("cargo:rerun-if-changed=vendor/ZenLib");
+    println!("cargo:rustc-link-search=native={}", install_dir.join("lib").display());
+    println!("cargo:rustc-link-search=native={}", install_dir.join("lib64").display());
+    println!("cargo:rustc-link-lib=static=mediainfo");
+    println!("cargo:rustc-link-lib=static=zen");
+
+    // NOTE(erick): Both libraries are C++, so the C++ runtime has to be
+    // linked explicitly when linking them statically.
+    let target = env::var("TARGET").unwrap();
+    if target.contains("apple") || target.contains("freebsd") {
+        println!("cargo:rustc-link-lib=c++");
+    } else if !target.contains("msvc") {
+        println!("cargo:rustc-link-lib=stdc++");
+    }
+}
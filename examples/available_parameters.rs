@@ -4,5 +4,16 @@ use mediainfo::MediaInfo;
 
 fn main() {
     let mut media_info = MediaInfo::new();
-    println!("{}", media_info.available_parameters().unwrap());
+    let catalog = media_info.parameter_catalog().unwrap();
+
+    for stream in catalog.streams.iter() {
+        println!("{}", stream.stream_type.name());
+
+        for parameter in stream.parameters.iter() {
+            match parameter.measure {
+                Some(ref measure) => println!("  {} ({}): {}", parameter.name, measure, parameter.description),
+                None => println!("  {}: {}", parameter.name, parameter.description),
+            }
+        }
+    }
 }
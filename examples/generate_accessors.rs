@@ -0,0 +1,21 @@
+// Regenerates src/generated_streams.rs from the parameter catalog of the
+// linked libmediainfo. Run from the repository root:
+//
+//     cargo run --example generate_accessors
+//
+// Newer libraries document more parameters, so rerun it (and review the
+// diff) when bumping the minimum supported MediaInfo version.
+
+extern crate mediainfo;
+
+use mediainfo::{generate_accessors, library_parameters};
+
+use std::fs;
+
+fn main() {
+    let catalog = library_parameters().expect("It should read the parameter catalog.");
+    let hand_written = fs::read_to_string("src/streams.rs").expect("It should read src/streams.rs.");
+
+    let generated = generate_accessors(&catalog, &hand_written);
+    fs::write("src/generated_streams.rs", generated).expect("It should write src/generated_streams.rs.");
+}
@@ -0,0 +1,87 @@
+// Bit rates, in bits per second, and the bit rate mode of a stream.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct BitRate {
+    pub bits_per_second: u64,
+}
+
+impl BitRate {
+    pub fn from_bps(bits_per_second: u64) -> BitRate {
+        BitRate { bits_per_second }
+    }
+
+    // NOTE(erick): Some streams report a fractional value ("1411200.5"),
+    // and streams with several bit rates (e.g. HE-AAC) report all of them
+    // ("128000 / 64000"), the first one being the effective one.
+    pub fn parse(bit_rate: &str) -> Option<BitRate> {
+        let value = bit_rate.split(" / ").next()?.trim().parse::<f64>().ok()?;
+        if value < 0.0 || !value.is_finite() { return None; }
+
+        Some(BitRate::from_bps(value.round() as u64))
+    }
+
+    pub fn bps(self) -> u64 {
+        self.bits_per_second
+    }
+
+    pub fn kbps(self) -> f64 {
+        self.bits_per_second as f64 / 1000.0
+    }
+
+    pub fn mbps(self) -> f64 {
+        self.bits_per_second as f64 / 1_000_000.0
+    }
+}
+
+impl fmt::Display for BitRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.bits_per_second >= 10_000_000 {
+            write!(f, "{:.1} Mb/s", self.mbps())
+        } else if self.bits_per_second >= 1_000 {
+            write!(f, "{:.0} kb/s", self.kbps())
+        } else {
+            write!(f, "{} b/s", self.bits_per_second)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BitRateMode {
+    Constant,
+    Variable,
+    Unknown,
+}
+
+impl BitRateMode {
+    // NOTE(erick): "BitRate_Mode" is "CBR" or "VBR", while its "/String"
+    // variant is "Constant" or "Variable". Mixed modes ("VBR / CBR") are
+    // reported for streams with several bit rates; the first one wins.
+    pub fn parse(mode: &str) -> BitRateMode {
+        match mode.split(" / ").next().unwrap_or("").trim() {
+            "CBR" | "Constant" => BitRateMode::Constant,
+            "VBR" | "Variable" => BitRateMode::Variable,
+            _ => BitRateMode::Unknown,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_bit_rates() {
+        assert_eq!(BitRate::parse("551194"), Some(BitRate::from_bps(551194)));
+        assert_eq!(BitRate::parse("128000 / 64000"), Some(BitRate::from_bps(128000)));
+        assert_eq!(BitRate::parse("1411200.5").unwrap().bps(), 1411201);
+        assert_eq!(BitRate::parse(""), None);
+        assert_eq!(BitRate::from_bps(551194).to_string(), "551 kb/s");
+        assert_eq!(BitRate::from_bps(25_000_000).to_string(), "25.0 Mb/s");
+
+        assert_eq!(BitRateMode::parse("CBR"), BitRateMode::Constant);
+        assert_eq!(BitRateMode::parse("Variable"), BitRateMode::Variable);
+        assert_eq!(BitRateMode::parse(""), BitRateMode::Unknown);
+    }
+}
@@ -1,9 +1,10 @@
 // A wrapper for converting Rust strings into C wide char strings
-// and vice-versa. The conversion uses the C API, which is the
-// best way that I could think of getting the correct result
-// always. Since it uses the C API, a call to 'setlocale()' must
-// be made before any of theses functions are called or some strange
-// behaviour may be encountered.
+// and vice-versa. The conversion is done by hand instead of going
+// through 'mbstowcs()'/'wcstombs()', so it does not depend on the
+// process locale and no call to 'setlocale()' is needed.
+//
+// 'wchar_t' holds UTF-32 code points everywhere but on Windows, where
+// it holds UTF-16 code units.
 
 // This implementation is not the most efficient way of making this
 // conversion (allocation-wise), but some trade-off has to be made
@@ -13,72 +14,141 @@
 #![allow(non_camel_case_types)]
 extern crate libc;
 
-use std::ptr;
 use std::path::Path;
-
-use std::ffi::CStr;
-use std::ffi::CString;
-
-type size_t = libc::size_t;
-type wchar  = libc::wchar_t;
+#[cfg(not(feature = "utf8"))]
+use std::slice;
+#[cfg(feature = "utf8")]
+use std::ffi::{CStr, CString};
+
+#[cfg(not(feature = "utf8"))]
+type wchar = libc::wchar_t;
+#[cfg(feature = "utf8")]
 type c_char = libc::c_char;
 
+#[cfg(not(feature = "utf8"))]
 pub struct CWcharString {
-    pub data: Vec<wchar>,
-    pub n_chars: usize,
+    data: Vec<wchar>,
 }
 
+#[cfg(not(feature = "utf8"))]
 impl CWcharString {
-    pub unsafe fn from_c_string(c_string: &CString) -> CWcharString {
-        let c_string_ptr =
-            (&c_string.as_bytes_with_nul()[0] as *const u8) as *const c_char;
+    pub fn from_str(string: &str) -> Result<CWcharString, ()> {
+        if string.contains('\0') { return Err( () ); }
+
+        let mut data = CWcharString::encode(string);
+        data.push(0 as wchar);
+
+        Ok(CWcharString { data })
+    }
+
+    pub fn from_path(in_path: &Path) -> Result<CWcharString, ()> {
+        match in_path.to_str() {
+            Some(p) => CWcharString::from_str(p),
+            None => Err( () ),
+        }
+    }
+
+    pub fn as_raw(&self) -> *const wchar {
+        self.data.as_ptr()
+    }
+
+    // NOTE(erick): `raw` must point to a NUL terminated wide string (or
+    // be null, which is treated as an empty string).
+    pub unsafe fn from_raw_to_string(raw: *const wchar) -> Result<String, ()> {
+        if raw.is_null() { return Ok(String::new()); }
+
+        let mut len = 0;
+        while *raw.add(len) != 0 {
+            len += 1;
+        }
 
-        let size_needed = mbstowcs(ptr::null_mut(), c_string_ptr, 0) + 1;
+        let data = slice::from_raw_parts(raw, len);
+        CWcharString::decode(data)
+    }
 
-        let mut data = vec![0 as wchar; size_needed as usize];
-        let wchar_ptr = &mut data.as_mut_slice()[0] as *mut wchar;
+    #[cfg(not(windows))]
+    fn encode(string: &str) -> Vec<wchar> {
+        string.chars().map(|c| c as u32 as wchar).collect()
+    }
 
-        let n_chars = mbstowcs(wchar_ptr, c_string_ptr, size_needed);
+    #[cfg(windows)]
+    fn encode(string: &str) -> Vec<wchar> {
+        string.encode_utf16().map(|c| c as wchar).collect()
+    }
 
-        CWcharString {
-            data,
-            n_chars: n_chars as usize,
+    #[cfg(not(windows))]
+    fn decode(data: &[wchar]) -> Result<String, ()> {
+        let mut result = String::with_capacity(data.len());
+        for &c in data {
+            match char::from_u32(c as u32) {
+                Some(c) => result.push(c),
+                None => return Err( () ),
+            }
         }
+
+        Ok(result)
     }
 
-    pub unsafe fn from_str(string: &str) -> Result<CWcharString, ()> {
-        let c_string = CString::new(string);
-        if c_string.is_err() { return Err( () ); }
+    #[cfg(windows)]
+    fn decode(data: &[wchar]) -> Result<String, ()> {
+        let units: Vec<u16> = data.iter().map(|&c| c as u16).collect();
+        String::from_utf16(&units).map_err(|_| ())
+    }
+}
+
+// NOTE(erick): The counterpart of CWcharString for the narrow (UTF-8)
+// libmediainfo API. Rust strings are already UTF-8, so this is mostly
+// a NUL terminator away.
+#[cfg(feature = "utf8")]
+pub struct CUtf8String {
+    data: CString,
+}
 
-        Ok(CWcharString::from_c_string(&c_string.unwrap()))
+#[cfg(feature = "utf8")]
+impl CUtf8String {
+    pub fn from_str(string: &str) -> Result<CUtf8String, ()> {
+        match CString::new(string) {
+            Ok(data) => Ok(CUtf8String { data }),
+            Err(_) => Err( () ),
+        }
     }
 
-    pub unsafe fn from_path(in_path: &Path) -> Result<CWcharString, ()> {
+    pub fn from_path(in_path: &Path) -> Result<CUtf8String, ()> {
         match in_path.to_str() {
-            Some(p) => CWcharString::from_str(p),
+            Some(p) => CUtf8String::from_str(p),
             None => Err( () ),
         }
     }
 
-    pub unsafe fn as_raw(&self) -> *const wchar {
-        &self.data[0] as *const wchar
+    pub fn as_raw(&self) -> *const c_char {
+        self.data.as_ptr()
     }
 
-    pub unsafe fn from_raw_to_c_string(raw: *const wchar) -> Result<CString, ()> {
-        let n_bytes = wcstombs(ptr::null_mut(), raw, 0);
+    pub unsafe fn from_raw_to_string(raw: *const c_char) -> Result<String, ()> {
+        if raw.is_null() { return Ok(String::new()); }
+
+        match CStr::from_ptr(raw).to_str() {
+            Ok(s) => Ok(s.to_owned()),
+            Err(_) => Err( () ),
+        }
+    }
+}
 
-        let mut data = vec![0 as u8; (n_bytes + 1) as usize];
-        let data_ptr = (&mut data[0] as *mut u8) as *mut c_char;
+#[cfg(all(test, not(feature = "utf8")))]
+mod tests {
+    use super::*;
 
-        wcstombs(data_ptr, raw, n_bytes + 1);
-        let c_str = CStr::from_bytes_with_nul(data.as_slice());
-        if c_str.is_err() { return Err( () ); }
+    #[test]
+    fn can_round_trip_non_ascii_strings() {
+        let original = "São Paulo – 東京 – 🎬.mkv";
+        let w_string = CWcharString::from_str(original).unwrap();
+        let result = unsafe { CWcharString::from_raw_to_string(w_string.as_raw()) };
 
-        Ok(c_str.unwrap().to_owned())
+        assert_eq!(Ok(original.to_string()), result);
     }
-}
 
-extern "C" {
-    fn mbstowcs(__pwcs: *mut wchar, __s: *const c_char, __n: size_t) -> size_t;
-    fn wcstombs(__s: *mut c_char, __pwcs: *const wchar, __n: size_t) -> size_t;
+    #[test]
+    fn rejects_interior_nul() {
+        assert!(CWcharString::from_str("a\0b").is_err());
+    }
 }
@@ -0,0 +1,303 @@
+// Speaker layout of an audio stream. MediaInfo reports it in two ways:
+//
+//     ChannelLayout    : L R C LFE Ls Rs
+//     ChannelPositions : Front: L C R, Side: L R, LFE
+//
+// Positions are named after ffmpeg's (FL, FR, FC, LFE, BL, BR, SL, SR,
+// TFL...), so layouts can be handed to ffmpeg as they are.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SpeakerPosition {
+    FrontLeft,
+    FrontRight,
+    FrontCenter,
+    LowFrequency,
+    BackLeft,
+    BackRight,
+    FrontLeftOfCenter,
+    FrontRightOfCenter,
+    BackCenter,
+    SideLeft,
+    SideRight,
+    TopCenter,
+    TopFrontLeft,
+    TopFrontCenter,
+    TopFrontRight,
+    TopBackLeft,
+    TopBackCenter,
+    TopBackRight,
+    TopSideLeft,
+    TopSideRight,
+    WideLeft,
+    WideRight,
+    LowFrequency2,
+    // NOTE(erick): Matrix encoded stereo (Dolby Surround Lt/Rt).
+    DownmixLeft,
+    DownmixRight,
+    Other(String),
+}
+
+impl SpeakerPosition {
+    // NOTE(erick): Parses the names used in the "ChannelLayout" field.
+    pub fn parse(name: &str) -> SpeakerPosition {
+        match name {
+            "L" => SpeakerPosition::FrontLeft,
+            "R" => SpeakerPosition::FrontRight,
+            "C" | "M" => SpeakerPosition::FrontCenter,
+            "LFE" => SpeakerPosition::LowFrequency,
+            "Ls" | "Lss" => SpeakerPosition::SideLeft,
+            "Rs" | "Rss" => SpeakerPosition::SideRight,
+            "Lb" | "Lrs" => SpeakerPosition::BackLeft,
+            "Rb" | "Rrs" => SpeakerPosition::BackRight,
+            "Cb" | "Cs" => SpeakerPosition::BackCenter,
+            "Lc" => SpeakerPosition::FrontLeftOfCenter,
+            "Rc" => SpeakerPosition::FrontRightOfCenter,
+            "Lw" => SpeakerPosition::WideLeft,
+            "Rw" => SpeakerPosition::WideRight,
+            "Tc" => SpeakerPosition::TopCenter,
+            "Tfl" | "Vhl" | "Lh" => SpeakerPosition::TopFrontLeft,
+            "Tfc" | "Vhc" => SpeakerPosition::TopFrontCenter,
+            "Tfr" | "Vhr" | "Rh" => SpeakerPosition::TopFrontRight,
+            "Tbl" => SpeakerPosition::TopBackLeft,
+            "Tbc" => SpeakerPosition::TopBackCenter,
+            "Tbr" => SpeakerPosition::TopBackRight,
+            "Tsl" => SpeakerPosition::TopSideLeft,
+            "Tsr" => SpeakerPosition::TopSideRight,
+            "LFE2" => SpeakerPosition::LowFrequency2,
+            "Lt" => SpeakerPosition::DownmixLeft,
+            "Rt" => SpeakerPosition::DownmixRight,
+            _ => SpeakerPosition::Other(name.to_string()),
+        }
+    }
+
+    pub fn ffmpeg_name(&self) -> &str {
+        match *self {
+            SpeakerPosition::FrontLeft          => "FL",
+            SpeakerPosition::FrontRight         => "FR",
+            SpeakerPosition::FrontCenter        => "FC",
+            SpeakerPosition::LowFrequency       => "LFE",
+            SpeakerPosition::BackLeft           => "BL",
+            SpeakerPosition::BackRight          => "BR",
+            SpeakerPosition::FrontLeftOfCenter  => "FLC",
+            SpeakerPosition::FrontRightOfCenter => "FRC",
+            SpeakerPosition::BackCenter         => "BC",
+            SpeakerPosition::SideLeft           => "SL",
+            SpeakerPosition::SideRight          => "SR",
+            SpeakerPosition::TopCenter          => "TC",
+            SpeakerPosition::TopFrontLeft       => "TFL",
+            SpeakerPosition::TopFrontCenter     => "TFC",
+            SpeakerPosition::TopFrontRight      => "TFR",
+            SpeakerPosition::TopBackLeft        => "TBL",
+            SpeakerPosition::TopBackCenter      => "TBC",
+            SpeakerPosition::TopBackRight       => "TBR",
+            SpeakerPosition::TopSideLeft        => "TSL",
+            SpeakerPosition::TopSideRight       => "TSR",
+            SpeakerPosition::WideLeft           => "WL",
+            SpeakerPosition::WideRight          => "WR",
+            SpeakerPosition::LowFrequency2      => "LFE2",
+            SpeakerPosition::DownmixLeft        => "DL",
+            SpeakerPosition::DownmixRight       => "DR",
+            SpeakerPosition::Other(ref x)       => x,
+        }
+    }
+
+    pub fn is_lfe(&self) -> bool {
+        matches!(*self, SpeakerPosition::LowFrequency | SpeakerPosition::LowFrequency2)
+    }
+
+    pub fn is_height(&self) -> bool {
+        matches!(*self,
+                 SpeakerPosition::TopCenter | SpeakerPosition::TopFrontLeft |
+                 SpeakerPosition::TopFrontCenter | SpeakerPosition::TopFrontRight |
+                 SpeakerPosition::TopBackLeft | SpeakerPosition::TopBackCenter |
+                 SpeakerPosition::TopBackRight | SpeakerPosition::TopSideLeft |
+                 SpeakerPosition::TopSideRight)
+    }
+}
+
+impl fmt::Display for SpeakerPosition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.ffmpeg_name())
+    }
+}
+
+// NOTE(erick): ffmpeg's named layouts, by speaker positions.
+const FFMPEG_LAYOUTS: &[(&str, &[&str])] = &[
+    ("mono",        &["FC"]),
+    ("stereo",      &["FL", "FR"]),
+    ("downmix",     &["DL", "DR"]),
+    ("2.1",         &["FL", "FR", "LFE"]),
+    ("3.0",         &["FL", "FR", "FC"]),
+    ("3.1",         &["FL", "FR", "FC", "LFE"]),
+    ("4.0",         &["FL", "FR", "FC", "BC"]),
+    ("quad",        &["FL", "FR", "BL", "BR"]),
+    ("quad(side)",  &["FL", "FR", "SL", "SR"]),
+    ("5.0",         &["FL", "FR", "FC", "BL", "BR"]),
+    ("5.0(side)",   &["FL", "FR", "FC", "SL", "SR"]),
+    ("5.1",         &["FL", "FR", "FC", "LFE", "BL", "BR"]),
+    ("5.1(side)",   &["FL", "FR", "FC", "LFE", "SL", "SR"]),
+    ("6.1",         &["FL", "FR", "FC", "LFE", "BC", "SL", "SR"]),
+    ("7.1",         &["FL", "FR", "FC", "LFE", "BL", "BR", "SL", "SR"]),
+    ("7.1(wide)",   &["FL", "FR", "FC", "LFE", "BL", "BR", "FLC", "FRC"]),
+    ("5.1.2",       &["FL", "FR", "FC", "LFE", "BL", "BR", "TFL", "TFR"]),
+    ("5.1.4",       &["FL", "FR", "FC", "LFE", "BL", "BR", "TFL", "TFR", "TBL", "TBR"]),
+    ("7.1.2",       &["FL", "FR", "FC", "LFE", "BL", "BR", "SL", "SR", "TFL", "TFR"]),
+    ("7.1.4",       &["FL", "FR", "FC", "LFE", "BL", "BR", "SL", "SR", "TFL", "TFR", "TBL", "TBR"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChannelLayout {
+    pub positions: Vec<SpeakerPosition>,
+}
+
+impl ChannelLayout {
+    // NOTE(erick): Parses the "ChannelLayout" field.
+    pub fn parse(layout: &str) -> Option<ChannelLayout> {
+        let positions: Vec<SpeakerPosition> = layout.split_whitespace().map(SpeakerPosition::parse).collect();
+        if positions.is_empty() { return None; }
+
+        Some(ChannelLayout { positions })
+    }
+
+    // NOTE(erick): Parses the older "ChannelPositions" field, where the
+    // speakers are grouped ("Front: L C R, Side: L R, Back: C, LFE").
+    pub fn parse_positions(channel_positions: &str) -> Option<ChannelLayout> {
+        let mut positions = Vec::new();
+
+        for group in channel_positions.split(',') {
+            let mut parts = group.splitn(2, ':');
+            let first = parts.next().unwrap_or("").trim();
+            let (group_name, speakers) = match parts.next() {
+                Some(speakers) => (first, speakers),
+                None => ("", first),
+            };
+
+            for speaker in speakers.split_whitespace() {
+                let position = match (group_name, speaker) {
+                    (_, "LFE")        => SpeakerPosition::LowFrequency,
+                    ("Front", "L")    => SpeakerPosition::FrontLeft,
+                    ("Front", "C")    => SpeakerPosition::FrontCenter,
+                    ("Front", "R")    => SpeakerPosition::FrontRight,
+                    ("Side", "L")     => SpeakerPosition::SideLeft,
+                    ("Side", "R")     => SpeakerPosition::SideRight,
+                    ("Back", "L")     => SpeakerPosition::BackLeft,
+                    ("Back", "C")     => SpeakerPosition::BackCenter,
+                    ("Back", "R")     => SpeakerPosition::BackRight,
+                    _ => SpeakerPosition::Other(format!("{}{}", group_name, speaker)),
+                };
+                positions.push(position);
+            }
+        }
+
+        if positions.is_empty() { return None; }
+
+        Some(ChannelLayout { positions })
+    }
+
+    pub fn channel_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn has_height(&self) -> bool {
+        self.positions.iter().any(|p| p.is_height())
+    }
+
+    // NOTE(erick): The usual "main.lfe" or "main.lfe.height" name, e.g.
+    // "2.0", "5.1" or "7.1.4".
+    pub fn common_name(&self) -> String {
+        let lfe = self.positions.iter().filter(|p| p.is_lfe()).count();
+        let height = self.positions.iter().filter(|p| p.is_height()).count();
+        let main = self.positions.len() - lfe - height;
+
+        if height > 0 {
+            format!("{}.{}.{}", main, lfe, height)
+        } else {
+            format!("{}.{}", main, lfe)
+        }
+    }
+
+    pub fn ffmpeg_name(&self) -> Option<&'static str> {
+        let mut names: Vec<&str> = self.positions.iter().map(|p| p.ffmpeg_name()).collect();
+        names.sort();
+
+        for &(layout, positions) in FFMPEG_LAYOUTS {
+            let mut positions = positions.to_vec();
+            positions.sort();
+            if positions == names { return Some(layout); }
+        }
+
+        None
+    }
+
+    pub fn is(&self, common_name: &str) -> bool {
+        self.common_name() == common_name
+    }
+
+    // NOTE(erick): Checks the positions, since dual mono ("M M", two
+    // independent channels) is "2.0" as well. Matrix encoded stereo
+    // (Lt Rt) counts as stereo.
+    pub fn is_stereo(&self) -> bool {
+        let has = |p: SpeakerPosition| self.positions.contains(&p);
+
+        self.positions.len() == 2 &&
+        ((has(SpeakerPosition::FrontLeft) && has(SpeakerPosition::FrontRight)) ||
+         (has(SpeakerPosition::DownmixLeft) && has(SpeakerPosition::DownmixRight)))
+    }
+
+    pub fn has_unknown_positions(&self) -> bool {
+        self.positions.iter().any(|p| matches!(*p, SpeakerPosition::Other(_)))
+    }
+
+    pub fn is_dual_mono(&self) -> bool {
+        self.positions == [SpeakerPosition::FrontCenter, SpeakerPosition::FrontCenter]
+    }
+}
+
+impl fmt::Display for ChannelLayout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let names: Vec<&str> = self.positions.iter().map(|p| p.ffmpeg_name()).collect();
+        write!(f, "{}", names.join("+"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_channel_layouts() {
+        let layout = ChannelLayout::parse("L R C LFE Ls Rs").unwrap();
+        assert_eq!(layout.common_name(), "5.1");
+        assert_eq!(layout.ffmpeg_name(), Some("5.1(side)"));
+        assert_eq!(layout.to_string(), "FL+FR+FC+LFE+SL+SR");
+
+        let atmos = ChannelLayout::parse("L R C LFE Ls Rs Lb Rb Tfl Tfr Tbl Tbr").unwrap();
+        assert_eq!(atmos.common_name(), "7.1.4");
+        assert_eq!(atmos.ffmpeg_name(), Some("7.1.4"));
+        assert!(atmos.has_height());
+
+        assert!(ChannelLayout::parse("M").unwrap().is("1.0"));
+        assert!(ChannelLayout::parse("L R").unwrap().is_stereo());
+        assert!(!ChannelLayout::parse("M M").unwrap().is_stereo());
+        assert!(ChannelLayout::parse("M M").unwrap().is_dual_mono());
+
+        let matrix = ChannelLayout::parse("Lt Rt").unwrap();
+        assert!(matrix.is_stereo());
+        assert_eq!(matrix.ffmpeg_name(), Some("downmix"));
+        assert!(!matrix.has_unknown_positions());
+        assert!(ChannelLayout::parse("X1 X2").unwrap().has_unknown_positions());
+        assert!(ChannelLayout::parse("").is_none());
+    }
+
+    #[test]
+    fn can_parse_channel_positions() {
+        let layout = ChannelLayout::parse_positions("Front: L C R, Side: L R, Back: L R, LFE").unwrap();
+        assert_eq!(layout.common_name(), "7.1");
+        assert_eq!(layout.ffmpeg_name(), Some("7.1"));
+
+        let stereo = ChannelLayout::parse_positions("Front: L R").unwrap();
+        assert_eq!(stereo.ffmpeg_name(), Some("stereo"));
+    }
+}
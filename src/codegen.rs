@@ -0,0 +1,232 @@
+// Generation of typed stream accessors from the parameter catalog. The
+// output is the contents of src/generated_streams.rs, which is included
+// at the end of streams.rs; examples/generate_accessors.rs writes it
+// using the catalog of the linked library.
+//
+// Accessors already written by hand in streams.rs are skipped, so the
+// hand-written ones (with their own names and types) always win.
+
+use ffi::MediaInfoStream;
+use parameters::{Parameter, ParameterCatalog};
+
+use std::collections::HashSet;
+
+const HEADER: &str = "// Generated by examples/generate_accessors.rs from MediaInfo's \
+                      parameter catalog. Do not edit by hand.\n";
+
+// NOTE(erick): Rust keywords are suffixed with '_' instead of using raw
+// identifiers, to keep the generated code readable.
+const KEYWORDS: &[&str] = &["as", "box", "break", "const", "continue", "crate", "else",
+                            "enum", "extern", "false", "fn", "for", "if", "impl", "in",
+                            "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+                            "return", "self", "static", "struct", "super", "trait", "true",
+                            "type", "unsafe", "use", "where", "while", "abstract", "become",
+                            "do", "final", "macro", "override", "priv", "typeof", "unsized",
+                            "virtual", "yield", "async", "await", "dyn", "try"];
+
+// NOTE(erick): "BitRate_Nominal" -> "bit_rate_nominal",
+// "Channel(s)" -> "channels", "Bits-(Pixel*Frame)" -> "bits_pixel_frame".
+pub fn accessor_name(parameter: &str) -> String {
+    let mut name = String::new();
+    let mut previous: Option<char> = None;
+
+    for c in parameter.chars() {
+        if c.is_ascii_alphanumeric() {
+            let starts_word = match previous {
+                Some(p) => c.is_ascii_uppercase() && (p.is_ascii_lowercase() || p.is_ascii_digit()),
+                None => false,
+            };
+            if starts_word && !name.ends_with('_') { name.push('_'); }
+
+            name.push(c.to_ascii_lowercase());
+        } else if c != '(' && c != ')' && !name.is_empty() && !name.ends_with('_') {
+            name.push('_');
+        }
+
+        previous = Some(c);
+    }
+
+    let mut name = name.trim_end_matches('_').to_string();
+    if name.starts_with(|c: char| c.is_ascii_digit()) { name.insert(0, '_'); }
+    if KEYWORDS.contains(&name.as_str()) { name.push('_'); }
+
+    name
+}
+
+// NOTE(erick): Delays (Delay, Video_Delay, Delay_Original...) are offsets
+// between streams and can be negative, which Duration can not hold.
+fn is_signed_duration(parameter: &Parameter) -> bool {
+    parameter.name.contains("Delay")
+}
+
+// NOTE(erick): The macro from streams.rs matching the parameter's type.
+// Dates are only recognized by name, since MediaInfo reports them as
+// text.
+pub fn accessor_macro(parameter: &Parameter) -> &'static str {
+    let value_type = parameter.options.chars().nth(3);
+    let numeric = value_type == Some('I') || value_type == Some('F');
+
+    if parameter.name.ends_with("_Date") || parameter.name.ends_with("_Date_Local") {
+        "mediainfo_date"
+    } else if numeric && parameter.measure.as_deref() == Some("ms") && is_signed_duration(parameter) {
+        if value_type == Some('I') { "mediainfo_i64" } else { "mediainfo_f64" }
+    } else if numeric && parameter.measure.as_deref() == Some("ms") {
+        "mediainfo_duration"
+    } else if numeric && parameter.measure.as_deref() == Some("bps") {
+        "mediainfo_bit_rate"
+    } else if value_type == Some('I') && parameter.measure.as_deref() == Some("byte") {
+        "mediainfo_u64"
+    } else if value_type == Some('I') {
+        "mediainfo_i64"
+    } else if value_type == Some('F') {
+        "mediainfo_f64"
+    } else {
+        "mediainfo_attr"
+    }
+}
+
+fn struct_name(stream_type: MediaInfoStream) -> String {
+    format!("{}Stream", stream_type.name())
+}
+
+// NOTE(erick): Names of the methods defined by hand in `source` (the
+// contents of streams.rs) for `struct_name`, plus the ones of the
+// BaseStream trait, which every stream has. Also returns the parameters
+// those methods read, since hand-written accessors are often named
+// differently ("ID" is `stream_id`, "StreamSize" is `container_size`).
+fn hand_written_accessors(source: &str, struct_name: &str) -> (HashSet<String>, HashSet<String>) {
+    let mut names = HashSet::new();
+    let mut parameters = HashSet::new();
+    let impl_line = format!("impl {} {{", struct_name);
+    let mut in_block = false;
+
+    for line in source.lines() {
+        if line.starts_with("impl ") || line.starts_with("pub trait ") {
+            in_block = line.starts_with(&impl_line) || line.starts_with("pub trait BaseStream");
+            continue;
+        }
+        if line.starts_with('}') { in_block = false; }
+        if !in_block { continue; }
+
+        let line = line.trim();
+        let name = if line.starts_with("mediainfo_") {
+            if let Some(parameter) = line.split('"').nth(1) {
+                parameters.insert(parameter.to_string());
+            }
+            line.split('(').nth(1).and_then(|rest| rest.split(',').next())
+        } else if line.starts_with("pub fn ") || line.starts_with("fn ") {
+            line.split("fn ").nth(1).and_then(|rest| rest.split(['(', '<']).next())
+        } else {
+            None
+        };
+
+        if let Some(name) = name {
+            names.insert(name.trim().to_string());
+        }
+    }
+
+    (names, parameters)
+}
+
+pub fn generate_accessors(catalog: &ParameterCatalog, hand_written: &str) -> String {
+    let mut output = String::from(HEADER);
+
+    for stream in catalog.streams.iter() {
+        let struct_name = struct_name(stream.stream_type);
+        let (mut taken, covered) = hand_written_accessors(hand_written, &struct_name);
+
+        let mut accessors = Vec::new();
+        for parameter in stream.parameters.iter() {
+            if covered.contains(&parameter.name) { continue; }
+
+            let name = accessor_name(&parameter.name);
+            if name.is_empty() || !taken.insert(name.clone()) { continue; }
+
+            accessors.push(format!("    {}!({}, \"{}\");\n",
+                                   accessor_macro(parameter), name, parameter.name));
+        }
+
+        if accessors.is_empty() { continue; }
+
+        output.push_str(&format!("\nimpl {} {{\n", struct_name));
+        for accessor in accessors {
+            output.push_str(&accessor);
+        }
+        output.push_str("}\n");
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_generate_accessors() {
+        assert_eq!(accessor_name("BitRate_Nominal"), "bit_rate_nominal");
+        assert_eq!(accessor_name("Channel(s)"), "channels");
+        assert_eq!(accessor_name("Bits-(Pixel*Frame)"), "bits_pixel_frame");
+        assert_eq!(accessor_name("Duration/String3"), "duration_string3");
+        assert_eq!(accessor_name("Type"), "type_");
+
+        let catalog = ParameterCatalog::parse_csv("Audio\n\
+                                                   Duration;; ms;N YFY;;;Play time of the stream in ms;\n\
+                                                   Delay;; ms;N YIY;;;Delay fixed in the stream;\n\
+                                                   Video_Delay;; ms;N YFY;;;Delay relative to the video stream;\n\
+                                                   SamplingRate;; Hz;N YFY;;;Sampling rate;\n\
+                                                   BitRate_Encoded;; bps;N YFY;;;Encoded bit rate;\n\
+                                                   StreamSize_Encoded;; byte;N YIY;;;Encoded stream size;\n\
+                                                   Channel(s);; channel;N YIY;;;Number of channels;\n\
+                                                   Encoded_Date;;;N YTY;;;The time that the encoding of this item was completed;\n\
+                                                   Encoded_Library;;;N YTY;;;Software used to create the file;\n");
+        let hand_written = "impl AudioStream {\n    mediainfo_duration!(duration, \"Duration\");\n}\n";
+
+        let generated = generate_accessors(&catalog, hand_written);
+        assert!(generated.contains("impl AudioStream {\n"));
+        assert!(!generated.contains("(duration,"));
+        assert!(generated.contains("mediainfo_i64!(delay, \"Delay\");"));
+        assert!(generated.contains("mediainfo_f64!(video_delay, \"Video_Delay\");"));
+        assert!(generated.contains("mediainfo_f64!(sampling_rate, \"SamplingRate\");"));
+        assert!(generated.contains("mediainfo_i64!(channels, \"Channel(s)\");"));
+        assert!(generated.contains("mediainfo_bit_rate!(bit_rate_encoded, \"BitRate_Encoded\");"));
+        assert!(generated.contains("mediainfo_u64!(stream_size_encoded, \"StreamSize_Encoded\");"));
+        assert!(generated.contains("mediainfo_date!(encoded_date, \"Encoded_Date\");"));
+        assert!(generated.contains("mediainfo_attr!(encoded_library, \"Encoded_Library\");"));
+    }
+
+    // NOTE(erick): Feeds the generator every parameter read by hand in the
+    // real streams.rs; none of them may come out again.
+    #[test]
+    fn skips_hand_written_accessors() {
+        let source = include_str!("streams.rs");
+
+        let mut csv = String::new();
+        let mut current = None;
+        for line in source.lines() {
+            if line.starts_with("impl ") && line.ends_with("Stream {") {
+                let struct_name = line.trim_start_matches("impl ").trim_end_matches(" {");
+                current = Some(struct_name.trim_end_matches("Stream").to_string());
+                csv.push_str(&format!("{}\n", current.as_ref().unwrap()));
+                continue;
+            }
+            if line.starts_with('}') { current = None; }
+
+            let line = line.trim();
+            if current.is_none() || !line.starts_with("mediainfo_") { continue; }
+            if let Some(parameter) = line.split('"').nth(1) {
+                csv.push_str(&format!("{};;;N YTY;;;;\n", parameter));
+            }
+        }
+        csv.push_str("General\nEncoded_OperatingSystem;;;N YTY;;;;\n");
+
+        let catalog = ParameterCatalog::parse_csv(&csv);
+        assert!(catalog.streams.len() >= 7);
+
+        let generated = generate_accessors(&catalog, source);
+        assert_eq!(generated,
+                   format!("{}\nimpl GeneralStream {{\n    \
+                            mediainfo_attr!(encoded_operating_system, \"Encoded_OperatingSystem\");\n}}\n",
+                           HEADER));
+    }
+}
@@ -1,7 +1,15 @@
-use ffi::{MediaInfo, MediaInfoResult, MediaInfoStream};
-use streams::{GeneralStream, VideoStream, AudioStream, ImageStream, MenuStream, OtherStream, TextStream};
+use ffi::{MediaInfo, MediaInfoInfo, MediaInfoResult, MediaInfoError, MediaInfoStream};
+use streams::{BaseStream, GeneralStream, VideoStream, AudioStream, ImageStream, MenuStream, OtherStream, TextStream};
 
-use chrono::{UTC, DateTime};
+use report::{MediaReport, StreamReport};
+use options::{AnalysisDepth, Options};
+use cover_art::CoverArt;
+use tags::Tags;
+use bit_rate::{BitRate, BitRateMode};
+use language::{self, TrackFlags};
+use timecode::TimeCode;
+
+use date::MediaDate;
 use std::rc::Rc;
 use std::cell::RefCell;
 use std::path::Path;
@@ -16,6 +24,11 @@ pub struct MediaInfoWrapper {
     menu_streams: Option<Vec<MenuStream>>,
     text_streams: Option<Vec<TextStream>>,
     other_streams: Option<Vec<OtherStream>>,
+    // NOTE(erick): None when ParseSpeed was set (through `set_options`) to
+    // a value that is not one of the depths.
+    analysis_depth: Option<AnalysisDepth>,
+    // NOTE(erick): The depth the currently opened file was analysed with.
+    opened_depth: Option<AnalysisDepth>,
 }
 
 impl Default for MediaInfoWrapper {
@@ -31,6 +44,8 @@ impl Default for MediaInfoWrapper {
             menu_streams: None,
             text_streams: None,
             other_streams: None,
+            analysis_depth: Some(AnalysisDepth::default()),
+            opened_depth: None,
             handle: Rc::new(RefCell::new(MediaInfo::new())),
         }
     }
@@ -41,6 +56,41 @@ impl MediaInfoWrapper {
         Default::default()
     }
 
+    // NOTE(erick): Options only affect files opened afterwards. They are
+    // applied one by one (see `Options::apply`) so that the depth follows
+    // ParseSpeed even if a later option is refused.
+    pub fn set_options(&mut self, options: &Options) -> MediaInfoResult<()> {
+        let mut handle = self.handle.borrow_mut();
+
+        for (name, value) in options.to_pairs()? {
+            handle.set_option(name, &value)?;
+
+            if name == "ParseSpeed" {
+                self.analysis_depth = value.parse().ok().and_then(AnalysisDepth::from_parse_speed);
+            }
+        }
+
+        Ok(())
+    }
+
+    // NOTE(erick): Like options, the depth only affects files opened
+    // afterwards.
+    pub fn set_analysis_depth(&mut self, depth: AnalysisDepth) -> MediaInfoResult<()> {
+        self.handle.borrow_mut().set_option("ParseSpeed", &depth.parse_speed().to_string())?;
+        self.analysis_depth = Some(depth);
+
+        Ok(())
+    }
+
+    pub fn analysis_depth(&self) -> Option<AnalysisDepth> {
+        self.analysis_depth
+    }
+
+    pub fn open_with_depth(&mut self, path: &Path, depth: AnalysisDepth) -> MediaInfoResult<usize> {
+        self.set_analysis_depth(depth)?;
+        self.open(path)
+    }
+
     pub fn open(&mut self, path: &Path) -> MediaInfoResult<usize> {
         let result = self.handle.borrow_mut().open(path);
 
@@ -78,10 +128,12 @@ impl MediaInfoWrapper {
         self.menu_streams = None;
         self.text_streams = None;
         self.other_streams = None;
+        self.opened_depth = None;
         self.handle.borrow_mut().close();
     }
 
     fn wrap_streams(&mut self) {
+        self.opened_depth = self.analysis_depth;
         self.general_stream.handler = Some(Rc::clone(&self.handle));
 
         for stype in MediaInfoStream::variants() {
@@ -93,6 +145,7 @@ impl MediaInfoWrapper {
                             stream_type: stype,
                             index: i,
                             handler: Rc::clone(&self.handle),
+                            depth: self.opened_depth,
                         });
                     };
                     self.video_streams = Some(streams);
@@ -104,6 +157,7 @@ impl MediaInfoWrapper {
                             stream_type: stype,
                             index: i,
                             handler: Rc::clone(&self.handle),
+                            depth: self.opened_depth,
                         });
                     };
                     self.audio_streams = Some(streams);
@@ -115,6 +169,7 @@ impl MediaInfoWrapper {
                             stream_type: stype,
                             index: i,
                             handler: Rc::clone(&self.handle),
+                            depth: self.opened_depth,
                         });
                     };
                     self.text_streams = Some(streams);
@@ -126,6 +181,7 @@ impl MediaInfoWrapper {
                             stream_type: stype,
                             index: i,
                             handler: Rc::clone(&self.handle),
+                            depth: self.opened_depth,
                         });
                     };
                     self.other_streams = Some(streams);
@@ -137,6 +193,7 @@ impl MediaInfoWrapper {
                             stream_type: stype,
                             index: i,
                             handler: Rc::clone(&self.handle),
+                            depth: self.opened_depth,
                         });
                     };
                     self.image_streams = Some(streams);
@@ -148,6 +205,7 @@ impl MediaInfoWrapper {
                             stream_type: stype,
                             index: i,
                             handler: Rc::clone(&self.handle),
+                            depth: self.opened_depth,
                         });
                     };
                     self.menu_streams = Some(streams);
@@ -157,6 +215,13 @@ impl MediaInfoWrapper {
         }
     }
 
+    // NOTE(erick): Most general stream fields are delegated to the
+    // wrapper below; this gives access to the rest (e.g. the generated
+    // accessors).
+    pub fn general_stream(&self) -> Option<&GeneralStream> {
+        if self.general_stream.handler.is_some() { Some(&self.general_stream) } else { None }
+    }
+
     pub fn video_streams(&self) -> Option<&Vec<VideoStream>> {
         self.video_streams.as_ref()
     }
@@ -181,6 +246,101 @@ impl MediaInfoWrapper {
         self.menu_streams.as_ref()
     }
 
+    // NOTE(erick): Whether any audio stream carries ReplayGain or dialnorm
+    // values.
+    pub fn has_loudness_metadata(&self) -> bool {
+        match self.audio_streams {
+            Some(ref streams) => streams.iter().any(|s| s.has_loudness_metadata()),
+            None => false,
+        }
+    }
+
+    // NOTE(erick): See `language::best_audio_track` for the rules.
+    pub fn best_audio_track(&self, preferences: &[&str]) -> Option<&AudioStream> {
+        let streams = self.audio_streams.as_ref()?;
+        let tracks: Vec<TrackFlags> = streams.iter().map(|s| TrackFlags {
+            language: s.language().ok(),
+            default: s.default().unwrap_or(false),
+            forced: s.forced().unwrap_or(false),
+        }).collect();
+
+        language::best_audio_track(&tracks, preferences).map(|i| &streams[i])
+    }
+
+    // NOTE(erick): See `language::best_text_track` for the rules.
+    pub fn best_text_track(&self, preferences: &[&str]) -> Option<&TextStream> {
+        let streams = self.text_streams.as_ref()?;
+        let tracks: Vec<TrackFlags> = streams.iter().map(|s| TrackFlags {
+            language: s.language().ok(),
+            default: s.default().unwrap_or(false),
+            forced: s.forced().unwrap_or(false),
+        }).collect();
+
+        language::best_text_track(&tracks, preferences).map(|i| &streams[i])
+    }
+
+    // NOTE(erick): Timecode tracks (as found in MOV and MXF files) are
+    // reported as "Time code" other streams and take precedence over
+    // the timecode of the video streams.
+    pub fn start_timecode(&self) -> Option<TimeCode> {
+        if let Some(ref streams) = self.other_streams {
+            let timecode = streams.iter()
+                .filter(|s| s.other_type().map(|t| t == "Time code").unwrap_or(false))
+                .filter_map(|s| s.time_code_first_frame().ok())
+                .next();
+            if timecode.is_some() { return timecode; }
+        }
+
+        match self.video_streams {
+            Some(ref streams) => streams.iter().filter_map(|s| s.time_code_first_frame().ok()).next(),
+            None => None,
+        }
+    }
+
+    pub fn stream_count(&self, stream_type: MediaInfoStream) -> usize {
+        match stream_type {
+            MediaInfoStream::General => if self.general_stream.handler.is_some() { 1 } else { 0 },
+            MediaInfoStream::Video => self.video_streams.as_ref().map_or(0, |s| s.len()),
+            MediaInfoStream::Audio => self.audio_streams.as_ref().map_or(0, |s| s.len()),
+            MediaInfoStream::Text  => self.text_streams.as_ref().map_or(0, |s| s.len()),
+            MediaInfoStream::Other => self.other_streams.as_ref().map_or(0, |s| s.len()),
+            MediaInfoStream::Image => self.image_streams.as_ref().map_or(0, |s| s.len()),
+            MediaInfoStream::Menu  => self.menu_streams.as_ref().map_or(0, |s| s.len()),
+            MediaInfoStream::Max   => 0,
+        }
+    }
+
+    pub fn report(&self) -> MediaInfoResult<MediaReport> {
+        let mut streams: Vec<&dyn BaseStream> = vec![&self.general_stream];
+        if let Some(ref s) = self.video_streams { streams.extend(s.iter().map(|x| x as &dyn BaseStream)); }
+        if let Some(ref s) = self.audio_streams { streams.extend(s.iter().map(|x| x as &dyn BaseStream)); }
+        if let Some(ref s) = self.text_streams  { streams.extend(s.iter().map(|x| x as &dyn BaseStream)); }
+        if let Some(ref s) = self.other_streams { streams.extend(s.iter().map(|x| x as &dyn BaseStream)); }
+        if let Some(ref s) = self.image_streams { streams.extend(s.iter().map(|x| x as &dyn BaseStream)); }
+        if let Some(ref s) = self.menu_streams  { streams.extend(s.iter().map(|x| x as &dyn BaseStream)); }
+
+        let mut report = MediaReport { streams: Vec::new(), depth: self.opened_depth };
+        for stream in streams {
+            report.streams.push(StreamReport {
+                stream_type: stream.stream_type(),
+                index: stream.index(),
+                fields: stream.fields()?,
+            });
+        }
+
+        Ok(report)
+    }
+
+    // NOTE(erick): Raw access to any field of any stream. Useful when
+    // the field has no typed accessor or when the field name is only
+    // known at runtime.
+    pub fn get(&self, stream_type: MediaInfoStream, index: usize, parameter: &str) -> MediaInfoResult<String> {
+        if self.general_stream.handler.is_none() { return Err(MediaInfoError::NoDataOpenError); }
+        if index >= self.stream_count(stream_type) { return Err(MediaInfoError::InvalidStreamIndexError); }
+
+        self.handle.borrow_mut().get(stream_type, index, parameter, MediaInfoInfo::Text, MediaInfoInfo::Name)
+    }
+
     delegate! {
         target self.general_stream {
             pub fn codec_id(&self) -> MediaInfoResult<String>;
@@ -189,16 +349,23 @@ impl MediaInfoWrapper {
             pub fn format_profile(&self) -> MediaInfoResult<String>;
             pub fn format_info(&self) -> MediaInfoResult<String>;
             pub fn codec(&self) -> MediaInfoResult<String>;
-            pub fn overall_bit_rate(&self) -> MediaInfoResult<i64>;
+            pub fn overall_bit_rate(&self) -> MediaInfoResult<BitRate>;
+            pub fn nominal_overall_bit_rate(&self) -> MediaInfoResult<BitRate>;
+            pub fn minimum_overall_bit_rate(&self) -> MediaInfoResult<BitRate>;
+            pub fn maximum_overall_bit_rate(&self) -> MediaInfoResult<BitRate>;
+            pub fn overall_bit_rate_mode(&self) -> BitRateMode;
             pub fn writing_application(&self) -> MediaInfoResult<String>;
-            pub fn headersize(&self) -> MediaInfoResult<i64>;
-            pub fn datasize(&self) -> MediaInfoResult<i64>;
-            pub fn footersize(&self) -> MediaInfoResult<i64>;
+            pub fn file_size(&self) -> MediaInfoResult<u64>;
+            pub fn container_size(&self) -> MediaInfoResult<u64>;
+            pub fn container_size_proportion(&self) -> MediaInfoResult<f64>;
+            pub fn headersize(&self) -> MediaInfoResult<u64>;
+            pub fn datasize(&self) -> MediaInfoResult<u64>;
+            pub fn footersize(&self) -> MediaInfoResult<u64>;
             pub fn encoded_library(&self) -> MediaInfoResult<String>;
-            pub fn mastered_date(&self) -> MediaInfoResult<DateTime<UTC>>;
-            pub fn tagged_date(&self) -> MediaInfoResult<DateTime<UTC>>;
-            pub fn encoded_date(&self) -> MediaInfoResult<DateTime<UTC>>;
-            pub fn last_modification_date(&self) -> MediaInfoResult<DateTime<UTC>>;
+            pub fn mastered_date(&self) -> MediaInfoResult<MediaDate>;
+            pub fn tagged_date(&self) -> MediaInfoResult<MediaDate>;
+            pub fn encoded_date(&self) -> MediaInfoResult<MediaDate>;
+            pub fn last_modification_date(&self) -> MediaInfoResult<MediaDate>;
             pub fn artist(&self) -> MediaInfoResult<String>;
             pub fn performer(&self) -> MediaInfoResult<String>;
             pub fn title(&self) -> MediaInfoResult<String>;
@@ -206,6 +373,8 @@ impl MediaInfoWrapper {
             pub fn album(&self) -> MediaInfoResult<String>;
             pub fn copyright(&self) -> MediaInfoResult<String>;
             pub fn year(&self) -> MediaInfoResult<String>;
+            pub fn cover_art(&self) -> Vec<CoverArt>;
+            pub fn tags(&self) -> MediaInfoResult<Tags>;
         }
     }
 }
@@ -214,7 +383,7 @@ impl MediaInfoWrapper {
 mod tests {
     use super::*;
     use std::path::PathBuf;
-    use chrono::NaiveDate;
+    use chrono::{NaiveDate, TimeZone, Utc};
     use std::fs;
 
     #[test]
@@ -230,12 +399,14 @@ mod tests {
         assert_eq!("MPEG-4", mw.format().unwrap());
         assert_eq!("Base Media / Version 2", mw.format_profile().unwrap());
         assert_eq!("MPEG-4", mw.codec().unwrap());
-        assert_eq!(551194, mw.overall_bit_rate().unwrap());
+        assert_eq!(BitRate::from_bps(551194), mw.overall_bit_rate().unwrap());
         assert_eq!("HandBrake 0.9.4 2009112300", mw.writing_application().unwrap());
         assert_eq!(160, mw.headersize().unwrap());
         assert_eq!(379880, mw.datasize().unwrap());
         assert_eq!(3591, mw.footersize().unwrap());
-        assert_eq!(DateTime::<UTC>::from_utc(NaiveDate::from_ymd(2010, 3, 20).and_hms(21, 29, 12), UTC), mw.tagged_date().unwrap());
+        assert_eq!(160 + 379880 + 3591, mw.file_size().unwrap());
+        let tagged_date = NaiveDate::from_ymd_opt(2010, 3, 20).unwrap().and_hms_opt(21, 29, 12).unwrap();
+        assert_eq!(Some(Utc.from_utc_datetime(&tagged_date)), mw.tagged_date().unwrap().to_utc());
         mw.close();
     }
 
@@ -270,5 +441,37 @@ mod tests {
         };
 
         assert_eq!("AVC", vstream.format().unwrap());
+        assert_eq!("Advanced Video Codec", vstream.format_info().unwrap());
+        assert!(vstream.frame_count().unwrap() > 0);
+    }
+
+    #[test]
+    fn can_build_report() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("sample.mp4");
+        let mut mw = MediaInfoWrapper::new();
+        mw.open(filename.as_path()).unwrap();
+
+        let report = mw.report().unwrap();
+        assert_eq!(report.streams_of(MediaInfoStream::General).len(), 1);
+        assert_eq!(report.streams_of(MediaInfoStream::Video)[0].get("Format"), Some("AVC"));
+        assert!(report.diff(&mw.report().unwrap()).is_empty());
+        assert!(matches!(mw.get(MediaInfoStream::Video, 1, "Format"), Err(MediaInfoError::InvalidStreamIndexError)));
+        assert_eq!(report.depth, Some(AnalysisDepth::Normal));
+    }
+
+    #[test]
+    fn can_open_with_depth() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("sample.mp4");
+        let mut mw = MediaInfoWrapper::new();
+        mw.open_with_depth(filename.as_path(), AnalysisDepth::Full).unwrap();
+
+        assert_eq!(mw.report().unwrap().depth, Some(AnalysisDepth::Full));
+
+        mw.set_options(&Options::new().parse_speed(0.3)).unwrap();
+        assert_eq!(mw.analysis_depth(), None);
+        mw.set_options(&Options::new().analysis_depth(AnalysisDepth::Quick)).unwrap();
+        assert_eq!(mw.analysis_depth(), Some(AnalysisDepth::Quick));
     }
 }
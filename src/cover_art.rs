@@ -0,0 +1,102 @@
+// Cover images embedded in the file (ID3 APIC, MP4 covr, FLAC pictures,
+// Matroska attachments...). MediaInfo only reports their data when the
+// "Cover_Data" option is set to "base64", with every cover of the file in
+// the same field, separated by " / ".
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverArt {
+    pub mime: Option<String>,
+    pub description: Option<String>,
+    pub bytes: Vec<u8>,
+}
+
+fn base64_value(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+// NOTE(erick): Accepts both the standard and the URL-safe alphabets, with
+// or without padding. Whitespace is skipped, since long values may be
+// wrapped.
+pub fn decode_base64(text: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut n_bits = 0;
+    let mut padding = false;
+
+    for c in text.bytes() {
+        if c.is_ascii_whitespace() { continue; }
+        if c == b'=' { padding = true; continue; }
+        if padding { return None; }
+
+        buffer = (buffer << 6) | base64_value(c)? as u32;
+        n_bits += 6;
+        if n_bits >= 8 {
+            n_bits -= 8;
+            bytes.push((buffer >> n_bits) as u8);
+            buffer &= (1 << n_bits) - 1;
+        }
+    }
+
+    // NOTE(erick): A single leftover character can not encode a byte.
+    if n_bits >= 6 { return None; }
+
+    Some(bytes)
+}
+
+fn split_values(text: &str) -> Vec<&str> {
+    if text.is_empty() { Vec::new() } else { text.split(" / ").collect() }
+}
+
+pub fn parse_covers(data: &str, mime: &str, description: &str) -> Vec<CoverArt> {
+    let mimes = split_values(mime);
+    let descriptions = split_values(description);
+    let non_empty = |x: Option<&&str>| x.map(|x| x.trim()).filter(|x| !x.is_empty()).map(|x| x.to_string());
+
+    let mut covers = Vec::new();
+    for (i, data) in split_values(data).into_iter().enumerate() {
+        let bytes = match decode_base64(data) {
+            Some(x) if !x.is_empty() => x,
+            _ => continue,
+        };
+
+        covers.push(CoverArt {
+            mime: non_empty(mimes.get(i)),
+            description: non_empty(descriptions.get(i)),
+            bytes,
+        });
+    }
+
+    covers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_decode_base64() {
+        assert_eq!(decode_base64("iVBORw0KGgo="), Some(vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]));
+        assert_eq!(decode_base64("TWFu\nTWE"), Some(b"ManMa".to_vec()));
+        assert_eq!(decode_base64("TW=Fu"), None);
+        assert_eq!(decode_base64("TWF*"), None);
+    }
+
+    #[test]
+    fn can_parse_covers() {
+        let covers = parse_covers("/9j/4A== / iVBORw==", "image/jpeg / image/png", "Cover (front) / ");
+
+        assert_eq!(covers.len(), 2);
+        assert_eq!(covers[0].mime, Some("image/jpeg".to_string()));
+        assert_eq!(covers[0].description, Some("Cover (front)".to_string()));
+        assert_eq!(covers[0].bytes, vec![0xFF, 0xD8, 0xFF, 0xE0]);
+        assert_eq!(covers[1].description, None);
+        assert!(parse_covers("", "", "").is_empty());
+    }
+}
@@ -0,0 +1,195 @@
+// Dates as MediaInfo reports them. Depending on the container and on the
+// tag format, they come in many forms:
+//
+//     UTC 2010-03-20 21:29:12        (MP4, QuickTime)
+//     2010-03-20 21:29:12 UTC
+//     2010-03-20T21:29:12+01:00      (ISO 8601, with or without fraction)
+//     2010-03-20 21:29:12            (local time, e.g. File_Modified_Date_Local)
+//     2010-03-20
+//     2010
+//
+// The text is kept as reported, since the parsed value loses details such
+// as the precision of the original.
+
+use chrono::{Datelike, DateTime, FixedOffset, NaiveDate, NaiveDateTime, TimeZone, Utc};
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DateValue {
+    DateTime(DateTime<FixedOffset>),
+    // NOTE(erick): A date and time without a timezone, usually local time.
+    Local(NaiveDateTime),
+    Date(NaiveDate),
+    Year(i32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaDate {
+    pub raw: String,
+    pub value: DateValue,
+}
+
+const DATE_TIME_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M:%S%.f",
+                                     "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"];
+const OFFSET_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S%.f%z", "%Y-%m-%dT%H:%M:%S%.f%z"];
+
+fn parse_value(text: &str) -> Option<DateValue> {
+    let (text, utc) = if let Some(x) = text.strip_prefix("UTC ") {
+        (x.trim(), true)
+    } else if let Some(x) = text.strip_suffix(" UTC") {
+        (x.trim(), true)
+    } else {
+        (text, false)
+    };
+
+    if let Ok(x) = DateTime::parse_from_rfc3339(text) {
+        return Some(DateValue::DateTime(x));
+    }
+    for format in OFFSET_FORMATS {
+        if let Ok(x) = DateTime::parse_from_str(text, format) {
+            return Some(DateValue::DateTime(x));
+        }
+    }
+
+    for format in DATE_TIME_FORMATS {
+        if let Ok(x) = NaiveDateTime::parse_from_str(text, format) {
+            if utc { return Some(DateValue::DateTime(Utc.from_utc_datetime(&x).into())); }
+            return Some(DateValue::Local(x));
+        }
+    }
+
+    if let Ok(x) = NaiveDate::parse_from_str(text, "%Y-%m-%d") {
+        return Some(DateValue::Date(x));
+    }
+
+    if text.len() == 4 && text.chars().all(|c| c.is_ascii_digit()) {
+        return text.parse().ok().map(DateValue::Year);
+    }
+
+    None
+}
+
+impl MediaDate {
+    // NOTE(erick): Fields with several values ("2010-03-20 / 2011") are
+    // parsed by their first one.
+    pub fn parse(date: &str) -> Option<MediaDate> {
+        let raw = date.trim();
+        let value = parse_value(raw.split(" / ").next()?.trim())?;
+
+        Some(MediaDate {
+            raw: raw.to_string(),
+            value,
+        })
+    }
+
+    // NOTE(erick): Only dates with a known timezone can be converted.
+    pub fn to_utc(&self) -> Option<DateTime<Utc>> {
+        match self.value {
+            DateValue::DateTime(ref x) => Some(x.with_timezone(&Utc)),
+            _ => None,
+        }
+    }
+
+    pub fn year(&self) -> i32 {
+        match self.value {
+            DateValue::DateTime(ref x) => x.year(),
+            DateValue::Local(ref x) => x.year(),
+            DateValue::Date(ref x) => x.year(),
+            DateValue::Year(x) => x,
+        }
+    }
+
+    pub fn date(&self) -> Option<NaiveDate> {
+        match self.value {
+            DateValue::DateTime(ref x) => Some(x.naive_local().date()),
+            DateValue::Local(ref x) => Some(x.date()),
+            DateValue::Date(x) => Some(x),
+            DateValue::Year(_) => None,
+        }
+    }
+}
+
+// NOTE(erick): Conversions for applications using the `time` crate instead
+// of chrono (the "time" feature).
+#[cfg(feature = "time")]
+impl MediaDate {
+    pub fn to_offset_date_time(&self) -> Option<time::OffsetDateTime> {
+        let x = match self.value {
+            DateValue::DateTime(ref x) => x,
+            _ => return None,
+        };
+
+        let offset = time::UtcOffset::from_whole_seconds(x.offset().local_minus_utc()).ok()?;
+        let utc = time::OffsetDateTime::from_unix_timestamp_nanos(i128::from(x.timestamp_nanos_opt()?)).ok()?;
+        Some(utc.to_offset(offset))
+    }
+
+    pub fn to_primitive_date_time(&self) -> Option<time::PrimitiveDateTime> {
+        use chrono::Timelike;
+
+        let x = match self.value {
+            DateValue::DateTime(ref x) => x.naive_local(),
+            DateValue::Local(x) => x,
+            _ => return None,
+        };
+
+        let time = time::Time::from_hms_nano(x.hour() as u8, x.minute() as u8, x.second() as u8, x.nanosecond()).ok()?;
+        Some(time::PrimitiveDateTime::new(to_time_date(x.date())?, time))
+    }
+
+    pub fn to_time_date(&self) -> Option<time::Date> {
+        to_time_date(self.date()?)
+    }
+}
+
+#[cfg(feature = "time")]
+fn to_time_date(date: NaiveDate) -> Option<time::Date> {
+    use std::convert::TryFrom;
+
+    let month = time::Month::try_from(date.month() as u8).ok()?;
+    time::Date::from_calendar_date(date.year(), month, date.day() as u8).ok()
+}
+
+impl fmt::Display for MediaDate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.raw)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_dates() {
+        let naive = NaiveDate::from_ymd_opt(2010, 3, 20).unwrap().and_hms_opt(21, 29, 12).unwrap();
+        let expected = Utc.from_utc_datetime(&naive);
+        assert_eq!(MediaDate::parse("UTC 2010-03-20 21:29:12").unwrap().to_utc(), Some(expected));
+        assert_eq!(MediaDate::parse("2010-03-20 21:29:12 UTC").unwrap().to_utc(), Some(expected));
+        assert_eq!(MediaDate::parse("2010-03-20T22:29:12+01:00").unwrap().to_utc(), Some(expected));
+        assert_eq!(MediaDate::parse("2010-03-20T21:29:12.250Z").unwrap().to_utc().unwrap().timestamp(),
+                   expected.timestamp());
+
+        let local = MediaDate::parse("2010-03-20 21:29:12").unwrap();
+        assert_eq!(local.value, DateValue::Local(naive));
+        assert_eq!(local.to_utc(), None);
+
+        assert_eq!(MediaDate::parse("2010-03-20").unwrap().value, DateValue::Date(naive.date()));
+        assert_eq!(MediaDate::parse("2010 / 2011").unwrap().value, DateValue::Year(2010));
+        assert_eq!(MediaDate::parse("2010").unwrap().to_string(), "2010");
+        assert!(MediaDate::parse("PAL").is_none());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn can_convert_to_time() {
+        let date = MediaDate::parse("2010-03-20T22:29:12+01:00").unwrap();
+        let converted = date.to_offset_date_time().unwrap();
+
+        assert_eq!(converted.unix_timestamp(), 1269120552);
+        assert_eq!(converted.offset().whole_hours(), 1);
+        assert_eq!(date.to_primitive_date_time().unwrap().hour(), 22);
+        assert_eq!(MediaDate::parse("2010").unwrap().to_time_date(), None);
+    }
+}
@@ -0,0 +1,70 @@
+// Runtime loading of libmediainfo (the "dynamic" feature). Instead of
+// linking against the library at build time, it is looked up the first
+// time a MediaInfo handle is created, in order:
+//
+//  1. the directories (or files) given to `set_library_search_path`;
+//  2. the directories listed in the MEDIAINFO_LIBRARY_PATH environment
+//     variable (separated like PATH);
+//  3. the default search path of the system's dynamic loader.
+//
+// If the library cannot be found, MediaInfo handles are still created,
+// but every call on them fails with `LibraryNotFoundError`.
+
+use libloading::Library;
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+#[cfg(target_os = "windows")]
+const LIBRARY_NAMES: &[&str] = &["MediaInfo.dll"];
+#[cfg(target_os = "macos")]
+const LIBRARY_NAMES: &[&str] = &["libmediainfo.0.dylib", "libmediainfo.dylib"];
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const LIBRARY_NAMES: &[&str] = &["libmediainfo.so.0", "libmediainfo.so"];
+
+static SEARCH_PATH: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+// NOTE(erick): Only has effect if called before the library is loaded,
+// i.e. before the first MediaInfo handle is created (or after a failed
+// attempt, since a missing library is looked up again every time).
+pub fn set_library_search_path(paths: &[PathBuf]) {
+    let mut search_path = SEARCH_PATH.lock().unwrap_or_else(|e| e.into_inner());
+    *search_path = paths.to_vec();
+}
+
+fn candidates() -> Vec<PathBuf> {
+    let mut dirs = SEARCH_PATH.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    if let Some(env_path) = env::var_os("MEDIAINFO_LIBRARY_PATH") {
+        dirs.extend(env::split_paths(&env_path));
+    }
+
+    let mut candidates = Vec::new();
+    for dir in dirs {
+        if dir.is_file() {
+            candidates.push(dir);
+            continue;
+        }
+
+        for name in LIBRARY_NAMES {
+            candidates.push(dir.join(name));
+        }
+    }
+
+    // NOTE(erick): Bare names are resolved by the system loader.
+    for name in LIBRARY_NAMES {
+        candidates.push(Path::new(name).to_path_buf());
+    }
+
+    candidates
+}
+
+pub fn open_library() -> Option<Library> {
+    for candidate in candidates() {
+        if let Ok(library) = unsafe { Library::new(&candidate) } {
+            return Some(library);
+        }
+    }
+
+    None
+}
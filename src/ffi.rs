@@ -1,23 +1,37 @@
-#![allow(non_camel_case_types)]
+#![allow(non_camel_case_types, non_snake_case)]
 extern crate libc;
 
-use ::c_w_string::CWcharString;
-use std::ffi::CString;
+#[cfg(not(feature = "utf8"))]
+use ::c_w_string::CWcharString as CApiString;
+#[cfg(feature = "utf8")]
+use ::c_w_string::CUtf8String as CApiString;
+use parameters::ParameterCatalog;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::Path;
+use std::ptr;
+#[cfg(feature = "dynamic")]
+use std::sync::Mutex;
+#[cfg(feature = "dynamic")]
+use libloading;
+#[cfg(feature = "dynamic")]
+use dynamic;
 
 type uint64 = libc::uint64_t;
 type uint8  = libc::uint8_t;
 type size_t = libc::size_t;
-type wchar  = libc::wchar_t;
-type c_char = libc::c_char;
-type c_int  = libc::c_int;
 type void   = libc::c_void;
 
+// NOTE(erick): The character type of the strings passed to and returned
+// by libmediainfo.
+#[cfg(not(feature = "utf8"))]
+type c_char_t = libc::wchar_t;
+#[cfg(feature = "utf8")]
+type c_char_t = libc::c_char;
+
 type c_MediaInfoStream = libc::c_int;
 type c_MediaInfoInfo   = libc::c_int;
 
-const LC_CTYPE: c_int = 0;
-
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum MediaInfoStream {
     General = 0,
@@ -40,6 +54,32 @@ impl MediaInfoStream {
        vec![MediaInfoStream::Video, MediaInfoStream::Audio, MediaInfoStream::Text,
        MediaInfoStream::Other, MediaInfoStream::Image, MediaInfoStream::Menu, MediaInfoStream::Max]
     }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            MediaInfoStream::General => "General",
+            MediaInfoStream::Video   => "Video",
+            MediaInfoStream::Audio   => "Audio",
+            MediaInfoStream::Text    => "Text",
+            MediaInfoStream::Other   => "Other",
+            MediaInfoStream::Image   => "Image",
+            MediaInfoStream::Menu    => "Menu",
+            MediaInfoStream::Max     => "Max",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<MediaInfoStream> {
+        match name {
+            "General" => Some(MediaInfoStream::General),
+            "Video"   => Some(MediaInfoStream::Video),
+            "Audio"   => Some(MediaInfoStream::Audio),
+            "Text"    => Some(MediaInfoStream::Text),
+            "Other"   => Some(MediaInfoStream::Other),
+            "Image"   => Some(MediaInfoStream::Image),
+            "Menu"    => Some(MediaInfoStream::Menu),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -67,11 +107,20 @@ pub struct MediaInfo {
 
 impl Default for MediaInfo {
     fn default() -> Self {
+        // NOTE(erick): Only possible with the "dynamic" feature. Every
+        // call on a null handle fails with `LibraryNotFoundError`.
+        if !library_available() {
+            return MediaInfo { handle: ptr::null_mut() };
+        }
+
         unsafe {
-            // NOTE(erick): Setting the locale so we can
-            // work properly with c wide strings.
-            let empty_c_str = CString::new("").unwrap();
-            setlocale(LC_CTYPE, empty_c_str.as_ptr());
+            #[cfg(feature = "utf8")]
+            {
+                let param = CApiString::from_str("CharSet").unwrap();
+                let value = CApiString::from_str("UTF-8").unwrap();
+                MediaInfo_Option(ptr::null_mut(), param.as_raw(), value.as_raw());
+            }
+
             MediaInfo {
                 handle : MediaInfo_New(),
             }
@@ -84,10 +133,35 @@ impl MediaInfo {
         Default::default()
     }
 
+    pub fn try_new() -> MediaInfoResult<MediaInfo> {
+        let media_info = MediaInfo::new();
+        if media_info.handle.is_null() { return Err(MediaInfoError::LibraryNotFoundError); }
+
+        Ok(media_info)
+    }
+
     pub fn open(&mut self, path: &Path) -> MediaInfoResult<usize> {
+        if self.handle.is_null() { return Err(MediaInfoError::LibraryNotFoundError); }
+
         unsafe {
-            let path_w_string = CWcharString::from_path(path);
-            if path_w_string.is_err() { return Err(MediaInfoError::RustToCStringError); }
+            let path_w_string = CApiString::from_path(path);
+            if path_w_string.is_err() {
+                // NOTE(erick): libmediainfo (ZenLib's File::Open) turns
+                // wide paths back into bytes with the process locale, or
+                // as UTF-8. A byte-to-wchar mapping would only survive
+                // that under a single-byte locale, and never under UTF-8,
+                // so no lossless wide string exists for a non-UTF-8 path.
+                // Instead, we open the file ourselves and feed it through
+                // the buffer API.
+                if cfg!(unix) && path.to_str().is_none() {
+                    let file = File::open(path);
+                    if file.is_err() { return Err(MediaInfoError::FileReadError); }
+
+                    return self.open_reader(&mut file.unwrap());
+                }
+
+                return Err(MediaInfoError::RustToCStringError);
+            }
 
             let path_w_string = path_w_string.unwrap();
             let path_ptr = path_w_string.as_raw();
@@ -100,6 +174,8 @@ impl MediaInfo {
 
 
     pub fn close(&mut self) {
+        if self.handle.is_null() { return; }
+
         unsafe {
             MediaInfo_Close(self.handle);
         }
@@ -107,41 +183,50 @@ impl MediaInfo {
 
 
     pub fn option(&mut self, parameter: &str, value: &str) -> MediaInfoResult<String> {
+        if self.handle.is_null() { return Err(MediaInfoError::LibraryNotFoundError); }
+
         unsafe {
-            let param_w_string = CWcharString::from_str(parameter);
-            let value_w_string = CWcharString::from_str(value);
+            let param_w_string = CApiString::from_str(parameter);
+            let value_w_string = CApiString::from_str(value);
 
             if param_w_string.is_err(){ return Err(MediaInfoError::RustToCStringError); }
             if value_w_string.is_err(){ return Err(MediaInfoError::RustToCStringError); }
 
-            let param_ptr = param_w_string.unwrap().as_raw();
-            let value_ptr = value_w_string.unwrap().as_raw();
+            let param_w_string = param_w_string.unwrap();
+            let value_w_string = value_w_string.unwrap();
+            let param_ptr = param_w_string.as_raw();
+            let value_ptr = value_w_string.as_raw();
 
             // TODO(erick): Do we need to free this memory? I could not
             // find this information on the documentation.
             let result_ptr = MediaInfo_Option(self.handle, param_ptr, value_ptr);
-            let result_c_string = CWcharString::from_raw_to_c_string(result_ptr);
-            if result_c_string.is_err() { return Err(MediaInfoError::CToRustError); }
-
-            let result = result_c_string.unwrap().into_string();
+            let result = CApiString::from_raw_to_string(result_ptr);
             if result.is_err() { return Err(MediaInfoError::CToRustError); }
 
-            let result = result.unwrap();
-            if result.is_empty() { return Err(MediaInfoError::ZeroLengthResultError); }
-
-            Ok(result)
+            // NOTE(erick): Setting an option usually succeeds with an empty
+            // reply, so an empty string is not an error here.
+            Ok(result.unwrap())
         }
     }
 
+    // NOTE(erick): Like `option`, but for options that set something.
+    // Unknown (or removed) options are reported instead of silently
+    // ignored.
+    pub fn set_option(&mut self, parameter: &str, value: &str) -> MediaInfoResult<()> {
+        let result = self.option(parameter, value)?;
+        if is_unsupported_option_reply(&result) { return Err(MediaInfoError::UnsupportedOptionError); }
+
+        Ok(())
+    }
+
     pub fn inform(&mut self) -> MediaInfoResult<String> {
+        if self.handle.is_null() { return Err(MediaInfoError::LibraryNotFoundError); }
+
         unsafe {
             // TODO(erick): Do we need to free this memory? I could not
             // find this information on the documentation.
             let result_ptr = MediaInfo_Inform(self.handle, 0 as size_t);
-            let result_c_string = CWcharString::from_raw_to_c_string(result_ptr);
-            if result_c_string.is_err() { return Err(MediaInfoError::CToRustError); }
-
-            let result = result_c_string.unwrap().into_string();
+            let result = CApiString::from_raw_to_string(result_ptr);
             if result.is_err() { return Err(MediaInfoError::CToRustError); }
 
             let result = result.unwrap();
@@ -152,6 +237,8 @@ impl MediaInfo {
     }
 
     pub fn count_get(&mut self, stream_kind: MediaInfoStream) -> usize {
+        if self.handle.is_null() { return 0; }
+
         unsafe {
             MediaInfo_Count_Get(self.handle, stream_kind.c_compatible(), (usize::max_value()) as size_t) as usize
         }
@@ -161,11 +248,14 @@ impl MediaInfo {
                stream_number: usize, parameter: &str,
                info_kind: MediaInfoInfo, search_kind: MediaInfoInfo)
                -> MediaInfoResult<String> {
+        if self.handle.is_null() { return Err(MediaInfoError::LibraryNotFoundError); }
+
         unsafe {
-            let param_w_string = CWcharString::from_str(parameter);
+            let param_w_string = CApiString::from_str(parameter);
             if param_w_string.is_err(){ return Err(MediaInfoError::RustToCStringError); }
 
-            let param_ptr = param_w_string.unwrap().as_raw();
+            let param_w_string = param_w_string.unwrap();
+            let param_ptr = param_w_string.as_raw();
 
             // TODO(erick): Do we need to free this memory? I could not
             // find this information on the documentation.
@@ -173,10 +263,7 @@ impl MediaInfo {
                                            stream_number as size_t, param_ptr,
                                            info_kind.c_compatible(),
                                            search_kind.c_compatible());
-            let result_c_string = CWcharString::from_raw_to_c_string(result_ptr);
-            if result_c_string.is_err() { return Err(MediaInfoError::CToRustError); }
-
-            let result = result_c_string.unwrap().into_string();
+            let result = CApiString::from_raw_to_string(result_ptr);
             if result.is_err() { return Err(MediaInfoError::CToRustError); }
 
             let result = result.unwrap();
@@ -186,15 +273,72 @@ impl MediaInfo {
         }
     }
 
+    // NOTE(erick): Number of fields (parameters) available for a
+    // given stream, as opposed to `count_get`, that returns the number
+    // of streams of a given kind.
+    pub fn count_parameters(&mut self, stream_kind: MediaInfoStream, stream_number: usize) -> usize {
+        if self.handle.is_null() { return 0; }
+
+        unsafe {
+            MediaInfo_Count_Get(self.handle, stream_kind.c_compatible(), stream_number as size_t) as usize
+        }
+    }
+
+    pub fn get_i(&mut self, info_stream: MediaInfoStream,
+                 stream_number: usize, parameter: usize,
+                 info_kind: MediaInfoInfo)
+                 -> MediaInfoResult<String> {
+        if self.handle.is_null() { return Err(MediaInfoError::LibraryNotFoundError); }
+
+        unsafe {
+            // TODO(erick): Do we need to free this memory? I could not
+            // find this information on the documentation.
+            let result_ptr = MediaInfo_GetI(self.handle, info_stream.c_compatible(),
+                                            stream_number as size_t, parameter as size_t,
+                                            info_kind.c_compatible());
+            let result = CApiString::from_raw_to_string(result_ptr);
+            if result.is_err() { return Err(MediaInfoError::CToRustError); }
+
+            let result = result.unwrap();
+            if result.is_empty() { return Err(MediaInfoError::ZeroLengthResultError); }
+
+            Ok(result)
+        }
+    }
+
     pub fn available_parameters(&mut self) -> MediaInfoResult<String> {
         self.option("Info_Parameters", "")
     }
 
+    // NOTE(erick): Older libraries may not support the CSV output, which
+    // is the only one with measures. Libraries without either output
+    // answer with a message instead, which is not a catalog.
+    pub fn parameter_catalog(&mut self) -> MediaInfoResult<ParameterCatalog> {
+        if let Ok(csv) = self.option("Info_Parameters_CSV", "") {
+            if !is_unsupported_option_reply(&csv) {
+                let catalog = ParameterCatalog::parse_csv(&csv);
+                if !catalog.streams.is_empty() { return Ok(catalog); }
+            }
+        }
+
+        let text = self.available_parameters()?;
+        if is_unsupported_option_reply(&text) { return Err(MediaInfoError::UnsupportedOptionError); }
+
+        let catalog = ParameterCatalog::parse(&text);
+        if catalog.streams.is_empty() { return Err(MediaInfoError::InvalidValueError); }
+
+        Ok(catalog)
+    }
+
     pub fn open_buffer_init(&mut self, buffer_size: u64, offset: u64) -> usize {
+        if self.handle.is_null() { return 0; }
+
         unsafe { MediaInfo_Open_Buffer_Init(self.handle, buffer_size, offset) as usize }
     }
 
     pub fn open_buffer_continue(&mut self, data: &[u8]) -> usize {
+        if self.handle.is_null() { return 0; }
+
         unsafe {
             let bytes_ptr = &data[0] as *const uint8;
             let result = MediaInfo_Open_Buffer_Continue(self.handle,
@@ -205,12 +349,60 @@ impl MediaInfo {
     }
 
     pub fn open_buffer_finalize(&mut self) -> usize {
+        if self.handle.is_null() { return 0; }
+
         unsafe { MediaInfo_Open_Buffer_Finalize(self.handle) as usize }
     }
+
+    // NOTE(erick): Returns the offset MediaInfo wants to continue
+    // reading from, if it wants to seek.
+    pub fn open_buffer_continue_goto_get(&mut self) -> Option<u64> {
+        if self.handle.is_null() { return None; }
+
+        let offset = unsafe { MediaInfo_Open_Buffer_Continue_GoTo_Get(self.handle) };
+        if offset == u64::MAX { None } else { Some(offset) }
+    }
+
+    // NOTE(erick): Feeds any seekable source through the buffer API,
+    // seeking whenever MediaInfo asks for it, like MediaInfo_Open does
+    // with files.
+    pub fn open_reader<R: Read + Seek>(&mut self, reader: &mut R) -> MediaInfoResult<usize> {
+        if self.handle.is_null() { return Err(MediaInfoError::LibraryNotFoundError); }
+
+        let size = reader.seek(SeekFrom::End(0));
+        if size.is_err() { return Err(MediaInfoError::FileReadError); }
+
+        let size = size.unwrap();
+        if reader.seek(SeekFrom::Start(0)).is_err() { return Err(MediaInfoError::FileReadError); }
+
+        self.open_buffer_init(size, 0);
+
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let n_read = match reader.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => n,
+                Err(_) => return Err(MediaInfoError::FileReadError),
+            };
+
+            // NOTE(erick): Bit 3 is set once MediaInfo has all it needs.
+            let status = self.open_buffer_continue(&buffer[..n_read]);
+            if status & 0x08 != 0 { break; }
+
+            if let Some(offset) = self.open_buffer_continue_goto_get() {
+                if reader.seek(SeekFrom::Start(offset)).is_err() { return Err(MediaInfoError::FileReadError); }
+                self.open_buffer_init(size, offset);
+            }
+        }
+
+        Ok(self.open_buffer_finalize())
+    }
 }
 
 impl Drop for MediaInfo {
     fn drop(&mut self) {
+        if self.handle.is_null() { return; }
+
         unsafe {
             MediaInfo_Delete(self.handle);
         }
@@ -224,44 +416,147 @@ pub enum MediaInfoError {
     ZeroLengthResultError,
     NonNumericResultError,
     NoDataOpenError,
+    InvalidStreamIndexError,
+    InvalidValueError,
+    RuleParseError(usize),
+    FileReadError,
+    LibraryNotFoundError,
+    UnsupportedOptionError,
+    DateParseError,
 }
 
 pub type MediaInfoResult<T> = Result<T, MediaInfoError>;
 
+// NOTE(erick): Options unknown to (or dropped by) the library are answered
+// with a message instead of an empty string.
+pub fn is_unsupported_option_reply(reply: &str) -> bool {
+    reply.starts_with("Option removed") || reply.starts_with("Option not known")
+}
+
 // NOTE(erick): This was needed in rust 1.6, keeping
 // here for historical purpose.
 // #[link(name="mediainfo")]
-extern "C" {
-    fn MediaInfo_New() -> *mut void;
+// NOTE(erick): Declares the libmediainfo functions we use. Normally they
+// are linked at build time; with the "dynamic" feature they are looked
+// up at runtime and called through a table of function pointers instead.
+// With the "utf8" feature the narrow (char*) variants of the API are used.
+// They take the same arguments, but strings are UTF-8 encoded (see the
+// "CharSet" option set in MediaInfo::default).
+macro_rules! mediainfo_api {
+    ($(fn $name: ident / $a_name: tt ($($arg: ident: $ty: ty),*) $(-> $ret: ty)*;)*) => (
+        #[cfg(not(feature = "dynamic"))]
+        extern "C" {
+            $(
+                #[cfg_attr(feature = "utf8", link_name = $a_name)]
+                fn $name($($arg: $ty),*) $(-> $ret)*;
+            )*
+        }
+
+        #[cfg(feature = "dynamic")]
+        struct MediaInfoLibrary {
+            _library: libloading::Library,
+            $($name: unsafe extern "C" fn($($ty),*) $(-> $ret)*,)*
+        }
+
+        #[cfg(feature = "dynamic")]
+        impl MediaInfoLibrary {
+            fn load(library: libloading::Library) -> Option<MediaInfoLibrary> {
+                unsafe {
+                    $(
+                        let symbol = if cfg!(feature = "utf8") {
+                            concat!($a_name, "\0")
+                        } else {
+                            concat!(stringify!($name), "\0")
+                        };
+                        let $name = match library.get::<unsafe extern "C" fn($($ty),*) $(-> $ret)*>(symbol.as_bytes()) {
+                            Ok(f) => *f,
+                            Err(_) => return None,
+                        };
+                    )*
+
+                    Some(MediaInfoLibrary {
+                        _library: library,
+                        $($name,)*
+                    })
+                }
+            }
+        }
+
+        $(
+            // NOTE(erick): Only called on non-null handles, which are only
+            // created once the library has been loaded.
+            #[cfg(feature = "dynamic")]
+            unsafe fn $name($($arg: $ty),*) $(-> $ret)* {
+                (library().expect("libmediainfo is not loaded").$name)($($arg),*)
+            }
+        )*
+    )
+}
+
+mediainfo_api! {
+    fn MediaInfo_New / "MediaInfoA_New" () -> *mut void;
+
+    fn MediaInfo_Delete / "MediaInfoA_Delete" (handle: *mut void);
+
+    fn MediaInfo_Open_Buffer_Init / "MediaInfoA_Open_Buffer_Init" (handle: *mut void,
+                                                                  buffer_size: uint64,
+                                                                  offset: uint64) -> size_t;
+
+    fn MediaInfo_Open_Buffer_Continue / "MediaInfoA_Open_Buffer_Continue" (handle: *mut void,
+                                                                          bytes: *const uint8,
+                                                                          length: size_t) -> size_t;
 
-    fn MediaInfo_Delete(handle: *mut void);
+    fn MediaInfo_Open_Buffer_Finalize / "MediaInfoA_Open_Buffer_Finalize" (handle: *mut void) -> size_t;
 
-    fn MediaInfo_Open_Buffer_Init(handle: *mut void,
-                                  buffer_size: uint64,
-                                  offset: uint64) -> size_t;
+    fn MediaInfo_Open_Buffer_Continue_GoTo_Get / "MediaInfoA_Open_Buffer_Continue_GoTo_Get" (handle: *mut void) -> uint64;
 
-    fn MediaInfo_Open_Buffer_Continue(handle: *mut void,
-                                      bytes: *const uint8,
-                                      length: size_t) -> size_t;
+    fn MediaInfo_Open / "MediaInfoA_Open" (handle: *mut void, path: *const c_char_t) -> size_t;
 
-    fn MediaInfo_Open_Buffer_Finalize(handle: *mut void) -> size_t;
+    fn MediaInfo_Close / "MediaInfoA_Close" (handle: *mut void);
 
-    fn MediaInfo_Open(handle: *mut void, path: *const wchar) -> size_t;
+    fn MediaInfo_Option / "MediaInfoA_Option" (handle: *mut void,
+                                               parameter: *const c_char_t,
+                                               value: *const c_char_t) -> *const c_char_t;
 
-    fn MediaInfo_Close(handle: *mut void);
+    fn MediaInfo_Inform / "MediaInfoA_Inform" (handle: *mut void, reserved: size_t) -> *const c_char_t;
 
-    fn MediaInfo_Option(handle: *mut void,
-                        parameter: *const wchar,
-                        value: *const wchar) -> *const wchar;
+    fn MediaInfo_Count_Get / "MediaInfoA_Count_Get" (handle: *mut void, stream_kind: c_MediaInfoStream,
+                                                     stream_number: size_t) -> size_t;
 
-    fn MediaInfo_Inform(handle: *mut void, reserved: size_t) -> *const wchar;
+    fn MediaInfo_Get / "MediaInfoA_Get" (handle: *mut void, info_stream: c_MediaInfoStream,
+                                         stream_number: size_t, parameter: *const c_char_t,
+                                         info_kind: c_MediaInfoInfo, search_kind: c_MediaInfoInfo)
+                                         -> *const c_char_t;
 
-    fn MediaInfo_Count_Get(handle: *mut void, stream_kind: c_MediaInfoStream, stream_number: size_t) -> size_t;
+    fn MediaInfo_GetI / "MediaInfoA_GetI" (handle: *mut void, info_stream: c_MediaInfoStream,
+                                           stream_number: size_t, parameter: size_t,
+                                           info_kind: c_MediaInfoInfo) -> *const c_char_t;
+}
+
+#[cfg(feature = "dynamic")]
+static LIBRARY: Mutex<Option<&'static MediaInfoLibrary>> = Mutex::new(None);
+
+// NOTE(erick): Loads the library on first use. A failed attempt is not
+// remembered, so a later call may succeed (e.g. after the search path
+// has been changed).
+#[cfg(feature = "dynamic")]
+fn library() -> Option<&'static MediaInfoLibrary> {
+    let mut library = LIBRARY.lock().unwrap_or_else(|e| e.into_inner());
+    if library.is_none() {
+        *library = dynamic::open_library()
+            .and_then(MediaInfoLibrary::load)
+            .map(|l| &*Box::leak(Box::new(l)));
+    }
 
-    fn MediaInfo_Get(handle: *mut void, info_stream: c_MediaInfoStream,
-                     stream_number: size_t, parameter: *const wchar,
-                     info_kind: c_MediaInfoInfo, search_kind: c_MediaInfoInfo)
-                     -> *const wchar;
+    *library
+}
+
+#[cfg(feature = "dynamic")]
+fn library_available() -> bool {
+    library().is_some()
+}
 
-    fn setlocale(category: c_int, locale: *const c_char) -> *const c_char;
+#[cfg(not(feature = "dynamic"))]
+fn library_available() -> bool {
+    true
 }
@@ -0,0 +1 @@
+// Generated by examples/generate_accessors.rs from MediaInfo's parameter catalog. Do not edit by hand.
@@ -0,0 +1,57 @@
+// Immersive (object based) audio formats. MediaInfo reports them through
+// the commercial name and the additional features of the base codec:
+//
+//     E-AC-3  + JOC       : Dolby Digital Plus with Dolby Atmos
+//     MLP FBA + 16-ch     : Dolby TrueHD with Dolby Atmos
+//     DTS     + XLL X     : DTS-HD Master Audio + DTS:X
+//     MPEG-H 3D Audio
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImmersiveFormat {
+    AtmosEac3,
+    AtmosTrueHd,
+    AtmosAc4,
+    DtsX,
+    MpegH,
+}
+
+impl ImmersiveFormat {
+    pub fn detect(format: &str, commercial_name: &str, additional_features: &str) -> Option<ImmersiveFormat> {
+        let features: Vec<&str> = additional_features.split_whitespace().collect();
+        let atmos = commercial_name.contains("Atmos");
+
+        match format {
+            "E-AC-3" if atmos || features.contains(&"JOC") => Some(ImmersiveFormat::AtmosEac3),
+            "MLP FBA" | "TrueHD" if atmos || features.contains(&"16-ch") => Some(ImmersiveFormat::AtmosTrueHd),
+            "AC-4" if atmos || features.contains(&"IMS") => Some(ImmersiveFormat::AtmosAc4),
+            // NOTE(erick): The "X" feature is also reported as part of
+            // "XLL X IMAX" for IMAX Enhanced streams.
+            "DTS" if commercial_name.contains("DTS:X") || features.contains(&"X") => Some(ImmersiveFormat::DtsX),
+            "MPEG-H 3D Audio" => Some(ImmersiveFormat::MpegH),
+            _ => None,
+        }
+    }
+
+    pub fn is_atmos(self) -> bool {
+        matches!(self, ImmersiveFormat::AtmosEac3 | ImmersiveFormat::AtmosTrueHd | ImmersiveFormat::AtmosAc4)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_detect_immersive_formats() {
+        assert_eq!(ImmersiveFormat::detect("E-AC-3", "Dolby Digital Plus with Dolby Atmos", "JOC"),
+                   Some(ImmersiveFormat::AtmosEac3));
+        assert_eq!(ImmersiveFormat::detect("MLP FBA", "", "16-ch"), Some(ImmersiveFormat::AtmosTrueHd));
+        assert_eq!(ImmersiveFormat::detect("DTS", "DTS-HD Master Audio + DTS:X", "XLL X"),
+                   Some(ImmersiveFormat::DtsX));
+        assert_eq!(ImmersiveFormat::detect("MPEG-H 3D Audio", "", ""), Some(ImmersiveFormat::MpegH));
+
+        assert_eq!(ImmersiveFormat::detect("E-AC-3", "Dolby Digital Plus", ""), None);
+        assert_eq!(ImmersiveFormat::detect("DTS", "DTS-HD Master Audio", "XLL"), None);
+        assert!(ImmersiveFormat::AtmosTrueHd.is_atmos());
+    }
+}
@@ -0,0 +1,220 @@
+// ISO 639 language codes, as found in the "Language" field of audio
+// and text streams. MediaInfo usually reports the two letter ISO 639-1
+// code (optionally followed by a region, e.g. "en-US"), but containers
+// that store the three letter ISO 639-2 code (e.g. "eng" or "ger") are
+// reported as-is, so both forms are accepted and can be compared.
+
+use std::fmt;
+use std::str::FromStr;
+
+// (ISO 639-1, ISO 639-2/T, ISO 639-2/B)
+const LANGUAGES: &[(&str, &str, &str)] = &[
+    ("ar", "ara", "ara"), ("bg", "bul", "bul"), ("bn", "ben", "ben"),
+    ("ca", "cat", "cat"), ("cs", "ces", "cze"), ("cy", "cym", "wel"),
+    ("da", "dan", "dan"), ("de", "deu", "ger"), ("el", "ell", "gre"),
+    ("en", "eng", "eng"), ("es", "spa", "spa"), ("et", "est", "est"),
+    ("eu", "eus", "baq"), ("fa", "fas", "per"), ("fi", "fin", "fin"),
+    ("fr", "fra", "fre"), ("ga", "gle", "gle"), ("gl", "glg", "glg"),
+    ("he", "heb", "heb"), ("hi", "hin", "hin"), ("hr", "hrv", "hrv"),
+    ("hu", "hun", "hun"), ("id", "ind", "ind"), ("is", "isl", "ice"),
+    ("it", "ita", "ita"), ("ja", "jpn", "jpn"), ("ko", "kor", "kor"),
+    ("lt", "lit", "lit"), ("lv", "lav", "lav"), ("ms", "msa", "may"),
+    ("nl", "nld", "dut"), ("no", "nor", "nor"), ("pl", "pol", "pol"),
+    ("pt", "por", "por"), ("ro", "ron", "rum"), ("ru", "rus", "rus"),
+    ("sk", "slk", "slo"), ("sl", "slv", "slv"), ("sr", "srp", "srp"),
+    ("sv", "swe", "swe"), ("ta", "tam", "tam"), ("te", "tel", "tel"),
+    ("th", "tha", "tha"), ("tr", "tur", "tur"), ("uk", "ukr", "ukr"),
+    ("ur", "urd", "urd"), ("vi", "vie", "vie"), ("zh", "zho", "chi"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LanguageCode {
+    primary: String,
+    region: Option<String>,
+}
+
+impl LanguageCode {
+    pub fn parse(code: &str) -> Option<LanguageCode> {
+        let mut parts = code.trim().splitn(2, ['-', '_']);
+        let primary = parts.next()?.to_lowercase();
+        let region = parts.next().map(|r| r.to_uppercase());
+
+        let valid_len = primary.len() == 2 || primary.len() == 3;
+        if !valid_len || !primary.chars().all(|c| c.is_ascii_lowercase()) {
+            return None;
+        }
+
+        Some(LanguageCode { primary, region })
+    }
+
+    // NOTE(erick): The language subtag exactly as reported, e.g. "en"
+    // for "en-US" or "eng" for "eng".
+    pub fn primary(&self) -> &str {
+        &self.primary
+    }
+
+    pub fn region(&self) -> Option<&str> {
+        self.region.as_deref()
+    }
+
+    pub fn iso639_1(&self) -> Option<&'static str> {
+        self.entry().map(|l| l.0)
+    }
+
+    pub fn iso639_2(&self) -> Option<&'static str> {
+        self.entry().map(|l| l.1)
+    }
+
+    // NOTE(erick): "und" (undetermined), "mul" (multiple) and "zxx"
+    // (no linguistic content) are not real languages.
+    pub fn is_undetermined(&self) -> bool {
+        self.primary == "und" || self.primary == "mul" || self.primary == "zxx"
+    }
+
+    // NOTE(erick): Two codes match when they name the same language,
+    // regardless of the ISO 639 form used and of the region.
+    pub fn matches(&self, other: &LanguageCode) -> bool {
+        if self.primary == other.primary { return true; }
+
+        match (self.entry(), other.entry()) {
+            (Some(a), Some(b)) => a.0 == b.0,
+            _ => false,
+        }
+    }
+
+    fn entry(&self) -> Option<&'static (&'static str, &'static str, &'static str)> {
+        let code = self.primary.as_str();
+        LANGUAGES.iter().find(|l| l.0 == code || l.1 == code || l.2 == code)
+    }
+}
+
+impl FromStr for LanguageCode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<LanguageCode, ()> {
+        LanguageCode::parse(s).ok_or(())
+    }
+}
+
+impl fmt::Display for LanguageCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.region {
+            Some(ref r) => write!(f, "{}-{}", self.primary, r),
+            None => write!(f, "{}", self.primary),
+        }
+    }
+}
+
+// NOTE(erick): What track selection needs to know about a track, so the
+// rules can be applied without an opened file.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackFlags {
+    pub language: Option<LanguageCode>,
+    pub default: bool,
+    pub forced: bool,
+}
+
+fn matching_tracks<'a>(tracks: &'a [TrackFlags], language: &LanguageCode) -> Vec<(usize, &'a TrackFlags)> {
+    tracks.iter()
+        .enumerate()
+        .filter(|&(_, t)| t.language.as_ref().map(|l| l.matches(language)).unwrap_or(false))
+        .collect()
+}
+
+// NOTE(erick): The index of the track of the first available language in
+// `preferences` (ISO 639 codes), preferring tracks flagged as default. If
+// none of the languages is available, the default track (or the first
+// one) is picked.
+pub fn best_audio_track(tracks: &[TrackFlags], preferences: &[&str]) -> Option<usize> {
+    for preference in preferences.iter().filter_map(|p| LanguageCode::parse(p)) {
+        let matching = matching_tracks(tracks, &preference);
+
+        if let Some(&(i, _)) = matching.iter().find(|&&(_, t)| t.default) { return Some(i); }
+        if let Some(&(i, _)) = matching.first() { return Some(i); }
+    }
+
+    tracks.iter()
+        .position(|t| t.default)
+        .or(if tracks.is_empty() { None } else { Some(0) })
+}
+
+// NOTE(erick): Like `best_audio_track`, but preferring full (not forced)
+// tracks before tracks flagged as default. Unlike audio, there is no
+// fallback: if none of the languages is available, no track is picked.
+pub fn best_text_track(tracks: &[TrackFlags], preferences: &[&str]) -> Option<usize> {
+    for preference in preferences.iter().filter_map(|p| LanguageCode::parse(p)) {
+        let matching = matching_tracks(tracks, &preference);
+        let full: Vec<&(usize, &TrackFlags)> = matching.iter().filter(|&&(_, t)| !t.forced).collect();
+
+        if let Some(&&(i, _)) = full.iter().find(|&&&(_, t)| t.default) { return Some(i); }
+        if let Some(&&(i, _)) = full.first() { return Some(i); }
+        if let Some(&(i, _)) = matching.first() { return Some(i); }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_language_codes() {
+        let code = LanguageCode::parse("en-us").unwrap();
+        assert_eq!(code.primary(), "en");
+        assert_eq!(code.region(), Some("US"));
+        assert_eq!(code.iso639_2(), Some("eng"));
+        assert_eq!(code.to_string(), "en-US");
+
+        assert!(LanguageCode::parse("english").is_none());
+        assert!(LanguageCode::parse("und").unwrap().is_undetermined());
+    }
+
+    #[test]
+    fn matches_across_iso_639_forms() {
+        let de = LanguageCode::parse("de").unwrap();
+        assert!(de.matches(&LanguageCode::parse("ger").unwrap()));
+        assert!(de.matches(&LanguageCode::parse("deu").unwrap()));
+        assert!(de.matches(&LanguageCode::parse("de-AT").unwrap()));
+        assert!(!de.matches(&LanguageCode::parse("en").unwrap()));
+    }
+
+    fn track(language: &str, default: bool, forced: bool) -> TrackFlags {
+        TrackFlags { language: LanguageCode::parse(language), default, forced }
+    }
+
+    #[test]
+    fn can_pick_audio_tracks() {
+        let tracks = [track("en", false, false), track("de", false, false),
+                      track("ger", true, false), track("fr", true, false)];
+
+        // NOTE(erick): The default track wins among the matching ones.
+        assert_eq!(best_audio_track(&tracks, &["de"]), Some(2));
+        // NOTE(erick): Without a default one, the first matching track.
+        assert_eq!(best_audio_track(&tracks, &["en", "de"]), Some(0));
+        // NOTE(erick): Unavailable and invalid preferences are skipped.
+        assert_eq!(best_audio_track(&tracks, &["ja", "??", "de"]), Some(2));
+        // NOTE(erick): No language available: the default track...
+        assert_eq!(best_audio_track(&tracks, &["ja"]), Some(2));
+        // NOTE(erick): ...or the first one.
+        assert_eq!(best_audio_track(&tracks[..2], &["ja"]), Some(0));
+        assert_eq!(best_audio_track(&[track("", false, false)], &["en"]), Some(0));
+        assert_eq!(best_audio_track(&[], &["en"]), None);
+    }
+
+    #[test]
+    fn can_pick_text_tracks() {
+        let tracks = [track("en", true, true), track("en", false, false),
+                      track("en", true, false), track("de", false, true)];
+
+        // NOTE(erick): A full default track beats a forced default one.
+        assert_eq!(best_text_track(&tracks, &["en"]), Some(2));
+        // NOTE(erick): Without a default one, the first full track.
+        assert_eq!(best_text_track(&tracks[..2], &["en"]), Some(1));
+        // NOTE(erick): Forced tracks are picked when they are all there is.
+        assert_eq!(best_text_track(&tracks, &["de"]), Some(3));
+        // NOTE(erick): No fallback when no language is available.
+        assert_eq!(best_text_track(&tracks, &["fr"]), None);
+        assert_eq!(best_text_track(&tracks, &["fr", "deu"]), Some(3));
+    }
+}
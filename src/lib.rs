@@ -1,18 +1,94 @@
 #![recursion_limit="1024"]
 extern crate libc;
 extern crate chrono;
+#[cfg(feature = "time")]
+extern crate time;
 #[macro_use]
 extern crate delegate;
+#[cfg(feature = "dynamic")]
+extern crate libloading;
+#[cfg(feature = "bundled")]
+extern crate libz_sys;
 
 mod c_w_string;
 mod ffi;
 mod streams;
 mod convenience_api;
+mod rules;
+mod report;
+mod language;
+mod subtitle;
+mod timecode;
+mod parameters;
+mod library;
+mod codegen;
+mod options;
+mod cover_art;
+mod tags;
+mod loudness;
+mod channel_layout;
+mod immersive;
+mod bit_rate;
+mod precision;
+mod date;
+#[cfg(feature = "dynamic")]
+mod dynamic;
 
 pub type MediaInfoWrapper = convenience_api::MediaInfoWrapper;
 pub type MediaInfo = ffi::MediaInfo;
 pub type MediaInfoResult<T> = ffi::MediaInfoResult<T>;
 pub type MediaInfoError = ffi::MediaInfoError;
+pub type MediaInfoStream = ffi::MediaInfoStream;
+
+pub type Options = options::Options;
+pub type OutputFormat = options::OutputFormat;
+pub type CoverData = options::CoverData;
+pub type DemuxMode = options::DemuxMode;
+pub type AnalysisDepth = options::AnalysisDepth;
+pub type CoverArt = cover_art::CoverArt;
+pub type Tags = tags::Tags;
+pub type Loudness = loudness::Loudness;
+pub type ChannelLayout = channel_layout::ChannelLayout;
+pub type SpeakerPosition = channel_layout::SpeakerPosition;
+pub type ImmersiveFormat = immersive::ImmersiveFormat;
+pub type BitRate = bit_rate::BitRate;
+pub type BitRateMode = bit_rate::BitRateMode;
+pub type Precision = precision::Precision;
+pub type MediaDate = date::MediaDate;
+pub type DateValue = date::DateValue;
+
+pub type Version = library::Version;
+pub type Codec = library::Codec;
+pub type Capacity = library::Capacity;
+pub type Parameter = parameters::Parameter;
+pub type StreamParameters = parameters::StreamParameters;
+pub type ParameterCatalog = parameters::ParameterCatalog;
+
+pub use library::{library_version, library_codecs, library_capacities, library_parameters};
+// NOTE(erick): Only public for examples/generate_accessors.rs.
+#[doc(hidden)]
+pub use codegen::generate_accessors;
+#[cfg(feature = "dynamic")]
+pub use dynamic::set_library_search_path;
+
+pub type LanguageCode = language::LanguageCode;
+pub type SubtitleFormat = subtitle::SubtitleFormat;
+pub type FrameRate = timecode::FrameRate;
+pub type TimeCode = timecode::TimeCode;
+
+pub type Rule = rules::Rule;
+pub type RuleSet = rules::RuleSet;
+pub type RuleOperator = rules::RuleOperator;
+pub type RuleResult = rules::RuleResult;
+pub type RuleReport = rules::RuleReport;
+
+pub type MediaReport = report::MediaReport;
+pub type StreamReport = report::StreamReport;
+pub type ReportDiff = report::ReportDiff;
+pub type StreamDiff = report::StreamDiff;
+pub type FieldChange = report::FieldChange;
+pub type DiffOptions = report::DiffOptions;
+pub type StreamPairing = report::StreamPairing;
 
 
 #[cfg(test)]
@@ -34,4 +110,31 @@ mod tests {
 
         m.close();
     }
+
+    #[test]
+    #[cfg(unix)]
+    fn can_open_non_utf8_paths() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+        use std::fs;
+
+        // NOTE(erick): Removes the copy even when an assertion fails.
+        struct TempFile(PathBuf);
+        impl Drop for TempFile {
+            fn drop(&mut self) {
+                let _ = fs::remove_file(&self.0);
+            }
+        }
+
+        let sample_path = PathBuf::from("samples");
+        let latin1_name = OsStr::from_bytes(b"sample_\xe9t\xe9.mp4");
+        let file = TempFile(std::env::temp_dir().join(latin1_name));
+        fs::copy(sample_path.join("sample.mp4"), &file.0).unwrap();
+
+        let mut m = MediaInfo::new();
+        assert!(m.open(file.0.as_path()).is_ok());
+        assert_eq!(m.count_get(MediaInfoStream::Video), 1);
+
+        m.close();
+    }
 }
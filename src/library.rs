@@ -0,0 +1,239 @@
+// Introspection of the linked libmediainfo: its version and what it
+// knows about (codecs, capacities and parameters). Field names and
+// output formats change between releases, so applications can use this
+// to gate features and to log the exact library in use.
+
+use ffi::{MediaInfo, MediaInfoError, MediaInfoResult, MediaInfoStream, is_unsupported_option_reply};
+use parameters::ParameterCatalog;
+
+use std::cmp::Ordering;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    // NOTE(erick): The version as reported (e.g. "21.09"), since the
+    // numbers alone lose the zero padding of date based versions.
+    pub text: String,
+}
+
+impl Version {
+    // NOTE(erick): Accepts both the bare version ("0.7.94", "21.09") and
+    // the "Info_Version" output ("MediaInfoLib - v21.09").
+    pub fn parse(version: &str) -> Option<Version> {
+        let text = version.split_whitespace().last()?;
+        let text = text.trim_start_matches('v');
+
+        let mut numbers = [0u32; 3];
+        let mut n_parts = 0;
+        for (number, part) in numbers.iter_mut().zip(text.split('.')) {
+            *number = part.parse::<u32>().ok()?;
+            n_parts += 1;
+        }
+        if n_parts < 2 { return None; }
+
+        Some(Version {
+            major: numbers[0],
+            minor: numbers[1],
+            patch: numbers[2],
+            text: text.to_string(),
+        })
+    }
+
+    pub fn at_least(&self, major: u32, minor: u32) -> bool {
+        (self.major, self.minor) >= (major, minor)
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Version) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Version) -> Ordering {
+        (self.major, self.minor, self.patch).cmp(&(other.major, other.minor, other.patch))
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+// NOTE(erick): "Info_Codecs" has one codec per line, with the columns of
+// MediaInfoDLL's `InfoCodec` (Codec;Name;KindOfCodec;KindOfStream;
+// Description;Url), e.g.
+//
+//     0x00000001;RGB;4CC;V;Basic Windows bitmap format;http://www.fourcc.org/indexrgb.htm
+#[derive(Debug, Clone, PartialEq)]
+pub struct Codec {
+    // NOTE(erick): The identifier found in files (a FourCC, a codec ID...).
+    pub id: String,
+    pub name: String,
+    pub kind_of_codec: Option<String>,
+    pub stream_type: Option<MediaInfoStream>,
+    pub description: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Capacity {
+    pub format: String,
+    pub read: bool,
+    pub write: bool,
+}
+
+// NOTE(erick): Both tables have one entry per line, either ';' separated
+// or "name : value" formatted depending on the library version. Empty
+// columns are kept, since the columns are positional.
+fn parse_table(text: &str) -> Vec<Vec<String>> {
+    let mut rows = Vec::new();
+
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() { continue; }
+
+        let columns: Vec<String> = if line.contains(';') {
+            line.split(';').map(|c| c.trim().to_string()).collect()
+        } else {
+            line.splitn(2, " : ").map(|c| c.trim().to_string()).collect()
+        };
+
+        if columns[0].is_empty() { continue; }
+        rows.push(columns);
+    }
+
+    rows
+}
+
+fn column(row: &[String], i: usize) -> Option<String> {
+    row.get(i).filter(|c| !c.is_empty()).cloned()
+}
+
+// NOTE(erick): The stream kinds are abbreviated to their initial.
+fn stream_type_from_kind(kind: &str) -> Option<MediaInfoStream> {
+    match kind {
+        "G" => Some(MediaInfoStream::General),
+        "V" => Some(MediaInfoStream::Video),
+        "A" => Some(MediaInfoStream::Audio),
+        "T" => Some(MediaInfoStream::Text),
+        "O" => Some(MediaInfoStream::Other),
+        "I" => Some(MediaInfoStream::Image),
+        "M" => Some(MediaInfoStream::Menu),
+        _ => MediaInfoStream::from_name(kind),
+    }
+}
+
+fn parse_codecs(text: &str) -> Vec<Codec> {
+    parse_table(text).into_iter().map(|row| {
+        Codec {
+            id: row[0].clone(),
+            name: column(&row, 1).unwrap_or_else(|| row[0].clone()),
+            kind_of_codec: column(&row, 2),
+            stream_type: column(&row, 3).and_then(|k| stream_type_from_kind(&k)),
+            description: column(&row, 4),
+            url: column(&row, 5),
+        }
+    }).collect()
+}
+
+// NOTE(erick): Capacities are listed as "AAC;Read/Write" or "AAC : Read",
+// the capability being "Read", "Write", both, or their initials.
+fn parse_capacities(text: &str) -> Vec<Capacity> {
+    parse_table(text).into_iter().map(|row| {
+        let mut capacity = Capacity { format: row[0].clone(), read: false, write: false };
+
+        for value in row.iter().skip(1) {
+            for part in value.split(['/', ',', ' ']).map(|x| x.trim().to_lowercase()) {
+                match part.as_str() {
+                    "read" | "r" => capacity.read = true,
+                    "write" | "w" => capacity.write = true,
+                    "rw" => { capacity.read = true; capacity.write = true; },
+                    _ => {},
+                }
+            }
+        }
+
+        capacity
+    }).collect()
+}
+
+fn library_option(option: &str) -> MediaInfoResult<String> {
+    let mut media_info = MediaInfo::try_new()?;
+    let result = media_info.option(option, "")?;
+    if is_unsupported_option_reply(&result) { return Err(MediaInfoError::UnsupportedOptionError); }
+
+    Ok(result)
+}
+
+pub fn library_version() -> MediaInfoResult<Version> {
+    match Version::parse(&library_option("Info_Version")?) {
+        Some(x) => Ok(x),
+        None => Err(MediaInfoError::InvalidValueError),
+    }
+}
+
+pub fn library_codecs() -> MediaInfoResult<Vec<Codec>> {
+    Ok(parse_codecs(&library_option("Info_Codecs")?))
+}
+
+pub fn library_capacities() -> MediaInfoResult<Vec<Capacity>> {
+    Ok(parse_capacities(&library_option("Info_Capacities")?))
+}
+
+pub fn library_parameters() -> MediaInfoResult<ParameterCatalog> {
+    MediaInfo::try_new()?.parameter_catalog()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_versions() {
+        let version = Version::parse("MediaInfoLib - v21.09").unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (21, 9, 0));
+        assert_eq!(version.to_string(), "21.09");
+        assert!(version.at_least(20, 3));
+
+        let old = Version::parse("0.7.94").unwrap();
+        assert!(old < version);
+        assert!(Version::parse("MediaInfoLib").is_none());
+    }
+
+    #[test]
+    fn can_parse_codecs() {
+        let codecs = parse_codecs("0x00000001;RGB;4CC;V;Basic Windows bitmap format;http://www.fourcc.org/indexrgb.htm\n\
+                                   mp4a;AAC;;A;Advanced Audio Codec;\n\
+                                   \n\
+                                   FLAC\n");
+
+        assert_eq!(codecs.len(), 3);
+        assert_eq!(codecs[0], Codec {
+            id: "0x00000001".to_string(),
+            name: "RGB".to_string(),
+            kind_of_codec: Some("4CC".to_string()),
+            stream_type: Some(MediaInfoStream::Video),
+            description: Some("Basic Windows bitmap format".to_string()),
+            url: Some("http://www.fourcc.org/indexrgb.htm".to_string()),
+        });
+        assert_eq!(codecs[1].stream_type, Some(MediaInfoStream::Audio));
+        assert_eq!((codecs[1].kind_of_codec.clone(), codecs[1].url.clone()), (None, None));
+        assert_eq!(codecs[2].name, "FLAC");
+    }
+
+    #[test]
+    fn can_parse_capacities() {
+        let capacities = parse_capacities("AAC;Read/Write\nFLAC : Read\nMPEG-4;R W\nTIFF;\n");
+
+        assert_eq!(capacities[0], Capacity { format: "AAC".to_string(), read: true, write: true });
+        assert_eq!((capacities[1].read, capacities[1].write), (true, false));
+        assert_eq!((capacities[2].read, capacities[2].write), (true, true));
+        assert_eq!((capacities[3].read, capacities[3].write), (false, false));
+    }
+}
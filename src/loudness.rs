@@ -0,0 +1,64 @@
+// Loudness metadata carried by an audio stream: ReplayGain (MP3, FLAC,
+// Vorbis...) and the Dolby AC-3/E-AC-3 metadata. Gains and dialnorm are
+// in dB; the peak is a linear sample value (1.0 is full scale).
+
+// NOTE(erick): MediaInfo may report the unit along with the value
+// ("-31 dB"), and streams with several values all of them ("-27 / -31").
+pub fn parse_decibels(value: &str) -> Option<f64> {
+    let value = value.split(" / ").next()?.trim();
+    let value = value.strip_suffix("dB").unwrap_or(value).trim();
+
+    value.parse().ok()
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Loudness {
+    pub replay_gain: Option<f64>,
+    pub replay_gain_peak: Option<f64>,
+    pub dialnorm: Option<f64>,
+    // NOTE(erick): For streams where dialnorm changes over time.
+    pub dialnorm_minimum: Option<f64>,
+    pub dialnorm_maximum: Option<f64>,
+    pub dialnorm_average: Option<f64>,
+}
+
+impl Loudness {
+    pub fn has_replay_gain(&self) -> bool {
+        self.replay_gain.is_some() || self.replay_gain_peak.is_some()
+    }
+
+    pub fn has_dialnorm(&self) -> bool {
+        self.dialnorm.is_some() || self.dialnorm_average.is_some() ||
+        self.dialnorm_minimum.is_some() || self.dialnorm_maximum.is_some()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.has_replay_gain() && !self.has_dialnorm()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_summarize_loudness() {
+        assert!(Loudness::default().is_empty());
+
+        let loudness = Loudness { dialnorm: Some(-27.0), ..Default::default() };
+        assert!(loudness.has_dialnorm());
+        assert!(!loudness.has_replay_gain());
+        assert!(!loudness.is_empty());
+
+        let range = Loudness { dialnorm_minimum: Some(-31.0), dialnorm_maximum: Some(-27.0), ..Default::default() };
+        assert!(range.has_dialnorm());
+    }
+
+    #[test]
+    fn can_parse_decibels() {
+        assert_eq!(parse_decibels("-31 dB"), Some(-31.0));
+        assert_eq!(parse_decibels("-6.48dB"), Some(-6.48));
+        assert_eq!(parse_decibels("-27 / -31"), Some(-27.0));
+        assert_eq!(parse_decibels("dB"), None);
+    }
+}
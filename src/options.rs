@@ -0,0 +1,264 @@
+// Typed builder for the MediaInfo options most applications set before
+// opening a file. Values are validated before any of them is applied, so
+// a typo fails loudly instead of being silently ignored by the library.
+//
+//     let options = Options::new().parse_speed(1.0).output(OutputFormat::Json);
+//     options.apply(&mut media_info)?;
+
+use ffi::{MediaInfo, MediaInfoError, MediaInfoResult};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutputFormat {
+    Text,
+    Html,
+    Xml,
+    Json,
+    MiXml,
+    EbuCore,
+    PbCore,
+    // NOTE(erick): A custom template, e.g. "General;%FileSize%".
+    Template(String),
+}
+
+impl OutputFormat {
+    fn value(&self) -> &str {
+        match *self {
+            OutputFormat::Text        => "",
+            OutputFormat::Html        => "HTML",
+            OutputFormat::Xml         => "XML",
+            OutputFormat::Json        => "JSON",
+            OutputFormat::MiXml       => "MIXML",
+            OutputFormat::EbuCore     => "EBUCore",
+            OutputFormat::PbCore      => "PBCore",
+            OutputFormat::Template(ref x) => x,
+        }
+    }
+}
+
+// NOTE(erick): How much of the file MediaInfo reads. Quick only reads the
+// headers, so values like FrameCount or the bit rate of VBR streams may be
+// estimated; Full reads the whole file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnalysisDepth {
+    Quick,
+    // NOTE(erick): MediaInfo's own default ParseSpeed is 0.5.
+    #[default]
+    Normal,
+    Full,
+}
+
+impl AnalysisDepth {
+    pub fn parse_speed(self) -> f64 {
+        match self {
+            AnalysisDepth::Quick  => 0.0,
+            AnalysisDepth::Normal => 0.5,
+            AnalysisDepth::Full   => 1.0,
+        }
+    }
+
+    pub fn from_parse_speed(speed: f64) -> Option<AnalysisDepth> {
+        [AnalysisDepth::Quick, AnalysisDepth::Normal, AnalysisDepth::Full].iter()
+            .cloned()
+            .find(|d| d.parse_speed() == speed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoverData {
+    // NOTE(erick): Only the cover's metadata is reported.
+    None,
+    Base64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DemuxMode {
+    None,
+    Container,
+    Elementary,
+    All,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Options {
+    parse_speed: Option<f64>,
+    complete: Option<bool>,
+    language: Option<String>,
+    inform: Option<OutputFormat>,
+    output: Option<OutputFormat>,
+    read_by_human: Option<bool>,
+    test_continuous_file_names: Option<bool>,
+    cover_data: Option<CoverData>,
+    demux: Option<DemuxMode>,
+}
+
+fn flag(value: bool) -> String {
+    if value { "1".to_string() } else { "0".to_string() }
+}
+
+impl Options {
+    pub fn new() -> Options {
+        Default::default()
+    }
+
+    // NOTE(erick): From 0 (only the headers) to 1 (the whole file).
+    pub fn parse_speed(mut self, speed: f64) -> Options {
+        self.parse_speed = Some(speed);
+        self
+    }
+
+    pub fn analysis_depth(self, depth: AnalysisDepth) -> Options {
+        self.parse_speed(depth.parse_speed())
+    }
+
+    pub fn complete(mut self, complete: bool) -> Options {
+        self.complete = Some(complete);
+        self
+    }
+
+    // NOTE(erick): The language of the field names and values in `inform`,
+    // e.g. "en", "pt-BR" or "raw" for the internal names.
+    pub fn language(mut self, language: &str) -> Options {
+        self.language = Some(language.to_string());
+        self
+    }
+
+    pub fn inform(mut self, format: OutputFormat) -> Options {
+        self.inform = Some(format);
+        self
+    }
+
+    pub fn output(mut self, format: OutputFormat) -> Options {
+        self.output = Some(format);
+        self
+    }
+
+    pub fn read_by_human(mut self, read_by_human: bool) -> Options {
+        self.read_by_human = Some(read_by_human);
+        self
+    }
+
+    pub fn test_continuous_file_names(mut self, test: bool) -> Options {
+        self.test_continuous_file_names = Some(test);
+        self
+    }
+
+    pub fn cover_data(mut self, cover_data: CoverData) -> Options {
+        self.cover_data = Some(cover_data);
+        self
+    }
+
+    pub fn demux(mut self, demux: DemuxMode) -> Options {
+        self.demux = Some(demux);
+        self
+    }
+
+    // NOTE(erick): The (option, value) pairs to pass to MediaInfo, in the
+    // order they are applied.
+    pub fn to_pairs(&self) -> MediaInfoResult<Vec<(&'static str, String)>> {
+        let mut pairs = Vec::new();
+
+        if let Some(speed) = self.parse_speed {
+            if !(0.0..=1.0).contains(&speed) { return Err(MediaInfoError::InvalidValueError); }
+            pairs.push(("ParseSpeed", speed.to_string()));
+        }
+
+        if let Some(complete) = self.complete {
+            // NOTE(erick): MediaInfo expects an empty value to disable it.
+            pairs.push(("Complete", if complete { "1".to_string() } else { String::new() }));
+        }
+
+        if let Some(ref language) = self.language {
+            let valid = !language.is_empty() &&
+                        language.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if !valid { return Err(MediaInfoError::InvalidValueError); }
+            pairs.push(("Language", language.clone()));
+        }
+
+        let formats = [("Inform", &self.inform), ("Output", &self.output)];
+        for &(name, format) in formats.iter() {
+            if let Some(ref format) = *format {
+                if let OutputFormat::Template(ref x) = *format {
+                    if x.is_empty() { return Err(MediaInfoError::InvalidValueError); }
+                }
+                pairs.push((name, format.value().to_string()));
+            }
+        }
+
+        if let Some(read_by_human) = self.read_by_human {
+            pairs.push(("ReadByHuman", flag(read_by_human)));
+        }
+
+        if let Some(test) = self.test_continuous_file_names {
+            pairs.push(("File_TestContinuousFileNames", flag(test)));
+        }
+
+        if let Some(cover_data) = self.cover_data {
+            let value = match cover_data {
+                CoverData::None   => "",
+                CoverData::Base64 => "base64",
+            };
+            pairs.push(("Cover_Data", value.to_string()));
+        }
+
+        if let Some(demux) = self.demux {
+            let value = match demux {
+                DemuxMode::None       => "",
+                DemuxMode::Container  => "container",
+                DemuxMode::Elementary => "elementary",
+                DemuxMode::All        => "all",
+            };
+            pairs.push(("Demux", value.to_string()));
+        }
+
+        Ok(pairs)
+    }
+
+    // NOTE(erick): Invalid values are rejected before anything is set, but
+    // applying is not atomic: if the library refuses an option, the ones
+    // set before it stay set.
+    pub fn apply(&self, media_info: &mut MediaInfo) -> MediaInfoResult<()> {
+        for (name, value) in self.to_pairs()? {
+            media_info.set_option(name, &value)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_build_options() {
+        let options = Options::new().parse_speed(0.5)
+                                    .complete(false)
+                                    .output(OutputFormat::Json)
+                                    .cover_data(CoverData::Base64)
+                                    .demux(DemuxMode::Container);
+
+        assert_eq!(options.to_pairs().unwrap(),
+                   vec![("ParseSpeed", "0.5".to_string()),
+                        ("Complete", "".to_string()),
+                        ("Output", "JSON".to_string()),
+                        ("Cover_Data", "base64".to_string()),
+                        ("Demux", "container".to_string())]);
+    }
+
+    #[test]
+    fn rejects_invalid_options() {
+        assert!(Options::new().parse_speed(1.5).to_pairs().is_err());
+        assert!(Options::new().language("en;fr").to_pairs().is_err());
+        assert!(Options::new().inform(OutputFormat::Template(String::new())).to_pairs().is_err());
+        assert!(Options::new().language("pt-BR").to_pairs().is_ok());
+    }
+
+    #[test]
+    fn can_map_analysis_depth() {
+        let options = Options::new().analysis_depth(AnalysisDepth::Full);
+        assert_eq!(options.to_pairs().unwrap(), vec![("ParseSpeed", "1".to_string())]);
+        assert_eq!(AnalysisDepth::default().parse_speed(), 0.5);
+        assert_eq!(AnalysisDepth::from_parse_speed(1.0), Some(AnalysisDepth::Full));
+        assert_eq!(AnalysisDepth::from_parse_speed(0.3), None);
+    }
+}
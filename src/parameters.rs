@@ -0,0 +1,181 @@
+// The catalog of parameters (fields) libmediainfo knows about, parsed
+// from the "Info_Parameters_CSV" option. Every stream starts with a line
+// holding just its name, followed by one line per parameter with the
+// columns of `MediaInfoInfo` (Name;Text;Measure;Options;Name_Text;
+// Measure_Text;Info;HowTo):
+//
+//     General
+//     Count;;;N NI;;;Count of objects available in this stream;
+//     Duration;; ms;N YFY;;;Play time of the stream in ms;
+//     ...
+//     Video
+//     ...
+//
+// The plain "Info_Parameters" output (name padded to a column, a colon
+// and the description) is understood as well, but has no measures.
+
+use ffi::{MediaInfoInfo, MediaInfoStream};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    pub description: String,
+    pub measure: Option<String>,
+    // NOTE(erick): MediaInfo's flags for the parameter, e.g. "N YFY". The
+    // fourth one is the type of the value (I, F or T).
+    pub options: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamParameters {
+    pub stream_type: MediaInfoStream,
+    pub parameters: Vec<Parameter>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParameterCatalog {
+    pub streams: Vec<StreamParameters>,
+}
+
+impl ParameterCatalog {
+    pub fn parse(info_parameters: &str) -> ParameterCatalog {
+        let mut catalog = ParameterCatalog::default();
+
+        for line in info_parameters.lines() {
+            let line = line.trim_end();
+            if line.trim().is_empty() { continue; }
+
+            if let Some(stream_type) = MediaInfoStream::from_name(line.trim()) {
+                catalog.streams.push(StreamParameters {
+                    stream_type,
+                    parameters: Vec::new(),
+                });
+                continue;
+            }
+
+            // NOTE(erick): Lines before the first stream header can not
+            // be attributed to any stream.
+            let current = match catalog.streams.last_mut() {
+                Some(s) => s,
+                None => continue,
+            };
+
+            let mut parts = line.splitn(2, ':');
+            let name = parts.next().unwrap_or("").trim();
+            let description = parts.next().unwrap_or("").trim();
+            if name.is_empty() { continue; }
+
+            current.parameters.push(Parameter {
+                name: name.to_string(),
+                description: description.to_string(),
+                measure: None,
+                options: String::new(),
+            });
+        }
+
+        catalog
+    }
+
+    pub fn parse_csv(info_parameters: &str) -> ParameterCatalog {
+        let mut catalog = ParameterCatalog::default();
+
+        for line in info_parameters.lines() {
+            let columns: Vec<&str> = line.split(';').map(|c| c.trim()).collect();
+            if columns.iter().all(|c| c.is_empty()) { continue; }
+
+            if columns.len() == 1 {
+                if let Some(stream_type) = MediaInfoStream::from_name(columns[0]) {
+                    catalog.streams.push(StreamParameters {
+                        stream_type,
+                        parameters: Vec::new(),
+                    });
+                }
+                continue;
+            }
+
+            let current = match catalog.streams.last_mut() {
+                Some(s) => s,
+                None => continue,
+            };
+
+            let column = |i: usize| columns.get(i).cloned().unwrap_or("");
+            if column(0).is_empty() { continue; }
+
+            let measure = column(MediaInfoInfo::Measure as usize);
+            current.parameters.push(Parameter {
+                name: column(MediaInfoInfo::Name as usize).to_string(),
+                description: column(MediaInfoInfo::Info as usize).to_string(),
+                measure: if measure.is_empty() { None } else { Some(measure.to_string()) },
+                options: column(MediaInfoInfo::Options as usize).to_string(),
+            });
+        }
+
+        catalog
+    }
+
+    pub fn get(&self, stream_type: MediaInfoStream, name: &str) -> Option<&Parameter> {
+        self.parameters(stream_type).iter().find(|p| p.name == name)
+    }
+
+    pub fn contains(&self, stream_type: MediaInfoStream, name: &str) -> bool {
+        self.get(stream_type, name).is_some()
+    }
+
+    // NOTE(erick): Parameters whose name starts with `prefix`, ignoring
+    // case, in catalog order.
+    pub fn complete(&self, stream_type: MediaInfoStream, prefix: &str) -> Vec<&Parameter> {
+        let prefix = prefix.to_lowercase();
+        self.parameters(stream_type).iter()
+            .filter(|p| p.name.to_lowercase().starts_with(&prefix))
+            .collect()
+    }
+
+    pub fn parameters(&self, stream_type: MediaInfoStream) -> &[Parameter] {
+        match self.streams.iter().find(|s| s.stream_type == stream_type) {
+            Some(s) => &s.parameters,
+            None => &[],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_info_parameters() {
+        let catalog = ParameterCatalog::parse("General\n\
+                                               Count                                    : Count of objects available in this stream\n\
+                                               Duration                                 : Play time of the stream in ms\n\
+                                               \n\
+                                               Video\n\
+                                               Width                                    : Width (aperture size if present) in pixel\n");
+
+        assert_eq!(catalog.streams.len(), 2);
+        assert_eq!(catalog.parameters(MediaInfoStream::General).len(), 2);
+        assert_eq!(catalog.parameters(MediaInfoStream::General)[1].name, "Duration");
+        assert_eq!(catalog.parameters(MediaInfoStream::Video)[0].description,
+                   "Width (aperture size if present) in pixel");
+        assert!(catalog.parameters(MediaInfoStream::Audio).is_empty());
+    }
+
+    #[test]
+    fn can_parse_info_parameters_csv() {
+        let catalog = ParameterCatalog::parse_csv("General\n\
+                                                   Count;;;N NI;;;Count of objects available in this stream;\n\
+                                                   Duration;; ms;N YFY;;;Play time of the stream in ms;\n\
+                                                   Duration/String;;;Y NT;;;Play time in format : XXx YYy only;\n\
+                                                   Video\n\
+                                                   Width;; pixel;N YIY;;;Width (aperture size if present) in pixel;\n");
+
+        let duration = catalog.get(MediaInfoStream::General, "Duration").unwrap();
+        assert_eq!(duration.measure, Some("ms".to_string()));
+        assert_eq!(duration.description, "Play time of the stream in ms");
+        assert_eq!(catalog.get(MediaInfoStream::General, "Count").unwrap().measure, None);
+        assert_eq!(catalog.get(MediaInfoStream::General, "Count").unwrap().options, "N NI");
+
+        assert!(catalog.contains(MediaInfoStream::Video, "Width"));
+        assert!(!catalog.contains(MediaInfoStream::Video, "Duration"));
+        assert_eq!(catalog.complete(MediaInfoStream::General, "dur").len(), 2);
+    }
+}
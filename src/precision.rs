@@ -0,0 +1,48 @@
+// Whether a count or duration was measured in the stream itself or taken
+// from the container (or derived from other values). MediaInfo tells them
+// apart through the "<Field>_Source" fields, e.g. "FrameCount_Source",
+// which are only reported when the value does not come from the stream.
+
+use options::AnalysisDepth;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    Exact,
+    Estimated,
+}
+
+impl Precision {
+    // NOTE(erick): A quick analysis only reads the headers, so its counts
+    // are estimates whatever the source says. The same goes for a depth
+    // that is not known (ParseSpeed set to some other value).
+    pub fn from_source(source: Option<&str>, depth: Option<AnalysisDepth>) -> Precision {
+        match depth {
+            Some(AnalysisDepth::Normal) | Some(AnalysisDepth::Full) => {},
+            _ => return Precision::Estimated,
+        }
+
+        match source.and_then(|x| x.split(" / ").next()).map(|x| x.trim()) {
+            None | Some("") | Some("Stream") => Precision::Exact,
+            _ => Precision::Estimated,
+        }
+    }
+
+    pub fn is_exact(self) -> bool {
+        self == Precision::Exact
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_sources() {
+        let normal = Some(AnalysisDepth::Normal);
+        assert_eq!(Precision::from_source(Some("Stream"), normal), Precision::Exact);
+        assert_eq!(Precision::from_source(None, normal), Precision::Exact);
+        assert_eq!(Precision::from_source(Some("Container"), normal), Precision::Estimated);
+        assert_eq!(Precision::from_source(None, Some(AnalysisDepth::Quick)), Precision::Estimated);
+        assert_eq!(Precision::from_source(Some("Stream"), None), Precision::Estimated);
+    }
+}
@@ -0,0 +1,281 @@
+// A snapshot of every field of every stream of an opened file. Reports
+// outlive the MediaInfo handle that produced them, so they can be kept
+// around and compared, e.g. a source file against its re-encoded output.
+
+use ffi::MediaInfoStream;
+use options::AnalysisDepth;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamReport {
+    pub stream_type: MediaInfoStream,
+    pub index: usize,
+    pub fields: Vec<(String, String)>,
+}
+
+impl StreamReport {
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.fields.iter()
+            .find(|f| f.0 == name)
+            .map(|f| f.1.as_str())
+    }
+
+    fn key(&self, pairing: StreamPairing) -> String {
+        match pairing {
+            StreamPairing::Index => self.index.to_string(),
+            StreamPairing::Id => match self.get("ID") {
+                Some(id) => id.to_string(),
+                None => format!("#{}", self.index),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct MediaReport {
+    pub streams: Vec<StreamReport>,
+    // NOTE(erick): The depth the file was analysed with, if known. Values
+    // like FrameCount may be estimates below AnalysisDepth::Full.
+    pub depth: Option<AnalysisDepth>,
+}
+
+impl MediaReport {
+    pub fn streams_of(&self, stream_type: MediaInfoStream) -> Vec<&StreamReport> {
+        self.streams.iter().filter(|s| s.stream_type == stream_type).collect()
+    }
+
+    pub fn diff(&self, other: &MediaReport) -> ReportDiff {
+        self.diff_with(other, &DiffOptions::default())
+    }
+
+    // NOTE(erick): `self` is the old (source) report and `other` the new
+    // (output) one: 'added' means present only in `other`.
+    pub fn diff_with(&self, other: &MediaReport, options: &DiffOptions) -> ReportDiff {
+        let mut diff = ReportDiff::default();
+
+        for old in &self.streams {
+            let key = old.key(options.pairing);
+            let new = other.streams.iter()
+                .find(|s| s.stream_type == old.stream_type && s.key(options.pairing) == key);
+
+            match new {
+                Some(new) => {
+                    let stream_diff = diff_streams(old, new, options);
+                    if !stream_diff.is_empty() {
+                        diff.changed_streams.push(stream_diff);
+                    }
+                },
+                None => diff.removed_streams.push(old.clone()),
+            }
+        }
+
+        for new in &other.streams {
+            let key = new.key(options.pairing);
+            let paired = self.streams.iter()
+                .any(|s| s.stream_type == new.stream_type && s.key(options.pairing) == key);
+
+            if !paired {
+                diff.added_streams.push(new.clone());
+            }
+        }
+
+        diff
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StreamPairing {
+    Index,
+    Id,
+}
+
+#[derive(Debug, Clone)]
+pub struct DiffOptions {
+    pub pairing: StreamPairing,
+    pub tolerances: Vec<(String, f64)>,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        DiffOptions {
+            pairing: StreamPairing::Index,
+            tolerances: Vec::new(),
+        }
+    }
+}
+
+impl DiffOptions {
+    pub fn new() -> DiffOptions {
+        Default::default()
+    }
+
+    pub fn pair_by(mut self, pairing: StreamPairing) -> DiffOptions {
+        self.pairing = pairing;
+        self
+    }
+
+    // NOTE(erick): The tolerance is in the field's raw unit (e.g.
+    // milliseconds for "Duration", bits per second for "BitRate") and
+    // also covers the field's "/String" variants.
+    pub fn tolerance(mut self, field: &str, tolerance: f64) -> DiffOptions {
+        self.tolerances.push((field.to_string(), tolerance));
+        self
+    }
+
+    fn tolerance_for(&self, field: &str) -> Option<(&str, f64)> {
+        let base = field.split('/').next().unwrap_or(field);
+        self.tolerances.iter()
+            .find(|t| t.0 == base)
+            .map(|t| (t.0.as_str(), t.1))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    pub name: String,
+    pub old: String,
+    pub new: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamDiff {
+    pub stream_type: MediaInfoStream,
+    pub old_index: usize,
+    pub new_index: usize,
+    pub added: Vec<(String, String)>,
+    pub removed: Vec<(String, String)>,
+    pub changed: Vec<FieldChange>,
+}
+
+impl StreamDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ReportDiff {
+    pub added_streams: Vec<StreamReport>,
+    pub removed_streams: Vec<StreamReport>,
+    pub changed_streams: Vec<StreamDiff>,
+}
+
+impl ReportDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added_streams.is_empty() &&
+            self.removed_streams.is_empty() &&
+            self.changed_streams.is_empty()
+    }
+}
+
+fn within_tolerance(old: &StreamReport, new: &StreamReport, name: &str, options: &DiffOptions) -> bool {
+    let (base, tolerance) = match options.tolerance_for(name) {
+        Some(t) => t,
+        None => return false,
+    };
+
+    let old_value = old.get(base).and_then(|v| v.parse::<f64>().ok());
+    let new_value = new.get(base).and_then(|v| v.parse::<f64>().ok());
+
+    match (old_value, new_value) {
+        (Some(o), Some(n)) => (o - n).abs() <= tolerance,
+        _ => false,
+    }
+}
+
+fn diff_streams(old: &StreamReport, new: &StreamReport, options: &DiffOptions) -> StreamDiff {
+    let mut diff = StreamDiff {
+        stream_type: old.stream_type,
+        old_index: old.index,
+        new_index: new.index,
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+
+    for (name, old_value) in &old.fields {
+        match new.get(name) {
+            Some(new_value) => {
+                if new_value != old_value && !within_tolerance(old, new, name, options) {
+                    diff.changed.push(FieldChange {
+                        name: name.clone(),
+                        old: old_value.clone(),
+                        new: new_value.to_string(),
+                    });
+                }
+            },
+            None => diff.removed.push((name.clone(), old_value.clone())),
+        }
+    }
+
+    for (name, new_value) in &new.fields {
+        if old.get(name).is_none() {
+            diff.added.push((name.clone(), new_value.clone()));
+        }
+    }
+
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(stream_type: MediaInfoStream, index: usize, fields: &[(&str, &str)]) -> StreamReport {
+        StreamReport {
+            stream_type,
+            index,
+            fields: fields.iter().map(|f| (f.0.to_string(), f.1.to_string())).collect(),
+        }
+    }
+
+    #[test]
+    fn can_diff_reports() {
+        let old = MediaReport { streams: vec![
+            stream(MediaInfoStream::General, 0, &[("Format", "MPEG-4"), ("Duration", "5568"),
+                                                  ("Duration/String", "5 s 568 ms")]),
+            stream(MediaInfoStream::Video, 0, &[("Format", "AVC"), ("BitRate", "465641")]),
+            stream(MediaInfoStream::Text, 0, &[("Format", "UTF-8")]),
+        ], depth: None };
+        let new = MediaReport { streams: vec![
+            stream(MediaInfoStream::General, 0, &[("Format", "Matroska"), ("Duration", "5570"),
+                                                  ("Duration/String", "5 s 570 ms")]),
+            stream(MediaInfoStream::Video, 0, &[("Format", "HEVC"), ("BitRate", "300000"),
+                                                ("HDR_Format", "SMPTE ST 2086")]),
+            stream(MediaInfoStream::Audio, 0, &[("Format", "Opus")]),
+        ], depth: None };
+
+        let diff = old.diff_with(&new, &DiffOptions::new().tolerance("Duration", 5.0));
+
+        assert_eq!(diff.added_streams.len(), 1);
+        assert_eq!(diff.added_streams[0].stream_type, MediaInfoStream::Audio);
+        assert_eq!(diff.removed_streams.len(), 1);
+        assert_eq!(diff.removed_streams[0].stream_type, MediaInfoStream::Text);
+
+        assert_eq!(diff.changed_streams.len(), 2);
+        let general = &diff.changed_streams[0];
+        assert_eq!(general.changed.len(), 1);
+        assert_eq!(general.changed[0].name, "Format");
+
+        let video = &diff.changed_streams[1];
+        assert_eq!(video.changed.len(), 2);
+        assert_eq!(video.added, vec![("HDR_Format".to_string(), "SMPTE ST 2086".to_string())]);
+    }
+
+    #[test]
+    fn can_pair_streams_by_id() {
+        let old = MediaReport { streams: vec![
+            stream(MediaInfoStream::Audio, 0, &[("ID", "2"), ("Language", "en")]),
+            stream(MediaInfoStream::Audio, 1, &[("ID", "3"), ("Language", "pt")]),
+        ], depth: None };
+        let new = MediaReport { streams: vec![
+            stream(MediaInfoStream::Audio, 0, &[("ID", "3"), ("Language", "pt")]),
+        ], depth: None };
+
+        assert_eq!(old.diff(&new).changed_streams.len(), 1);
+
+        let diff = old.diff_with(&new, &DiffOptions::new().pair_by(StreamPairing::Id));
+        assert!(diff.changed_streams.is_empty());
+        assert_eq!(diff.removed_streams.len(), 1);
+        assert_eq!(diff.removed_streams[0].get("ID"), Some("2"));
+    }
+}
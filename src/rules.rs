@@ -0,0 +1,328 @@
+// A small declarative rules engine for validating media files against
+// an ingestion policy. Rule sets are plain text files with one rule
+// per line:
+//
+//     # name        : stream field             operator   expected value
+//     video_height  : Video  Height            ==         1080
+//     video_cfr     : Video  FrameRate_Mode    ==         CFR
+//     video_profile : Video  Format_Profile    starts_with High
+//     audio_rate    : Audio  SamplingRate      ==         48000
+//     audio_codec   : Audio  Format            in         AAC|AAC LC
+//     max_duration  : General Duration         <          7200000
+//
+// Field names and values are the raw ones returned by MediaInfo (e.g.
+// durations are in milliseconds), so any field listed by
+// `MediaInfo::available_parameters` can be used. Empty lines and lines
+// starting with '#' are ignored.
+
+use ffi::{MediaInfoError, MediaInfoResult, MediaInfoStream};
+use convenience_api::MediaInfoWrapper;
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RuleOperator {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Contains,
+    StartsWith,
+    OneOf,
+}
+
+impl RuleOperator {
+    pub fn from_name(name: &str) -> Option<RuleOperator> {
+        match name {
+            "=="          => Some(RuleOperator::Equal),
+            "!="          => Some(RuleOperator::NotEqual),
+            "<"           => Some(RuleOperator::Less),
+            "<="          => Some(RuleOperator::LessOrEqual),
+            ">"           => Some(RuleOperator::Greater),
+            ">="          => Some(RuleOperator::GreaterOrEqual),
+            "contains"    => Some(RuleOperator::Contains),
+            "starts_with" => Some(RuleOperator::StartsWith),
+            "in"          => Some(RuleOperator::OneOf),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            RuleOperator::Equal          => "==",
+            RuleOperator::NotEqual       => "!=",
+            RuleOperator::Less           => "<",
+            RuleOperator::LessOrEqual    => "<=",
+            RuleOperator::Greater        => ">",
+            RuleOperator::GreaterOrEqual => ">=",
+            RuleOperator::Contains       => "contains",
+            RuleOperator::StartsWith     => "starts_with",
+            RuleOperator::OneOf          => "in",
+        }
+    }
+
+    // NOTE(erick): Values that look like numbers are compared as
+    // numbers, so "48000" == "48000.0". Ordering operators only make
+    // sense for numbers and fail for anything else.
+    pub fn matches(self, actual: &str, expected: &str) -> bool {
+        let numbers = (actual.trim().parse::<f64>(), expected.trim().parse::<f64>());
+
+        match self {
+            RuleOperator::Equal => match numbers {
+                (Ok(a), Ok(e)) => a == e,
+                _ => actual == expected,
+            },
+            RuleOperator::NotEqual => !RuleOperator::Equal.matches(actual, expected),
+            RuleOperator::Less => match numbers {
+                (Ok(a), Ok(e)) => a < e,
+                _ => false,
+            },
+            RuleOperator::LessOrEqual => match numbers {
+                (Ok(a), Ok(e)) => a <= e,
+                _ => false,
+            },
+            RuleOperator::Greater => match numbers {
+                (Ok(a), Ok(e)) => a > e,
+                _ => false,
+            },
+            RuleOperator::GreaterOrEqual => match numbers {
+                (Ok(a), Ok(e)) => a >= e,
+                _ => false,
+            },
+            RuleOperator::Contains => actual.contains(expected),
+            RuleOperator::StartsWith => actual.starts_with(expected),
+            RuleOperator::OneOf => expected.split('|')
+                .any(|e| RuleOperator::Equal.matches(actual, e.trim())),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub stream_type: MediaInfoStream,
+    pub parameter: String,
+    pub operator: RuleOperator,
+    pub expected: String,
+}
+
+impl Rule {
+    pub fn new(name: &str, stream_type: MediaInfoStream, parameter: &str,
+               operator: RuleOperator, expected: &str) -> Rule {
+        Rule {
+            name: name.to_string(),
+            stream_type,
+            parameter: parameter.to_string(),
+            operator,
+            expected: expected.to_string(),
+        }
+    }
+
+    // NOTE(erick): Parses a single "name : Kind Field operator value"
+    // line. The value is the rest of the line, so it may contain spaces.
+    fn parse(line: &str) -> Option<Rule> {
+        let colon = line.find(':')?;
+        let name = line[..colon].trim();
+        if name.is_empty() { return None; }
+
+        let mut tokens = line[colon + 1..].split_whitespace();
+        let stream_type = MediaInfoStream::from_name(tokens.next()?)?;
+        let parameter = tokens.next()?;
+        let operator = RuleOperator::from_name(tokens.next()?)?;
+        let expected = tokens.collect::<Vec<&str>>().join(" ");
+        if expected.is_empty() { return None; }
+
+        Some(Rule::new(name, stream_type, parameter, operator, &expected))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    pub rules: Vec<Rule>,
+}
+
+impl RuleSet {
+    pub fn new() -> RuleSet {
+        Default::default()
+    }
+
+    pub fn from_file(path: &Path) -> MediaInfoResult<RuleSet> {
+        match fs::read_to_string(path) {
+            Ok(contents) => RuleSet::parse(&contents),
+            Err(_) => Err(MediaInfoError::FileReadError),
+        }
+    }
+
+    // NOTE(erick): Returns `RuleParseError` with the (1-based) number
+    // of the first line that could not be parsed.
+    pub fn parse(contents: &str) -> MediaInfoResult<RuleSet> {
+        let mut rules = Vec::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') { continue; }
+
+            match Rule::parse(line) {
+                Some(rule) => rules.push(rule),
+                None => return Err(MediaInfoError::RuleParseError(i + 1)),
+            }
+        }
+
+        Ok(RuleSet { rules })
+    }
+
+    pub fn push(&mut self, rule: Rule) {
+        self.rules.push(rule);
+    }
+
+    // NOTE(erick): Every rule is checked against every stream of its
+    // kind. A rule whose stream kind is not present in the file fails
+    // with no index, since the policy cannot be satisfied.
+    pub fn evaluate(&self, media_info: &MediaInfoWrapper) -> RuleReport {
+        let mut results = Vec::new();
+
+        for rule in &self.rules {
+            let count = media_info.stream_count(rule.stream_type);
+            if count == 0 {
+                results.push(RuleResult {
+                    rule: rule.name.clone(),
+                    stream_type: rule.stream_type,
+                    index: None,
+                    parameter: rule.parameter.clone(),
+                    operator: rule.operator,
+                    expected: rule.expected.clone(),
+                    actual: None,
+                    passed: false,
+                });
+                continue;
+            }
+
+            for index in 0..count {
+                let actual = media_info.get(rule.stream_type, index, &rule.parameter).ok();
+                let passed = match actual {
+                    Some(ref a) => rule.operator.matches(a, &rule.expected),
+                    None => false,
+                };
+
+                results.push(RuleResult {
+                    rule: rule.name.clone(),
+                    stream_type: rule.stream_type,
+                    index: Some(index),
+                    parameter: rule.parameter.clone(),
+                    operator: rule.operator,
+                    expected: rule.expected.clone(),
+                    actual,
+                    passed,
+                });
+            }
+        }
+
+        RuleReport { results }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RuleResult {
+    pub rule: String,
+    pub stream_type: MediaInfoStream,
+    pub index: Option<usize>,
+    pub parameter: String,
+    pub operator: RuleOperator,
+    pub expected: String,
+    pub actual: Option<String>,
+    pub passed: bool,
+}
+
+impl fmt::Display for RuleResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let status = if self.passed { "PASS" } else { "FAIL" };
+        let index = match self.index {
+            Some(i) => i.to_string(),
+            None => "-".to_string(),
+        };
+        let actual = match self.actual {
+            Some(ref a) => a.as_str(),
+            None => "<missing>",
+        };
+
+        write!(f, "[{}] {}: {} #{} {} {} {} (actual: {})",
+               status, self.rule, self.stream_type.name(), index,
+               self.parameter, self.operator.name(), self.expected, actual)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleReport {
+    pub results: Vec<RuleResult>,
+}
+
+impl RuleReport {
+    pub fn passed(&self) -> bool {
+        self.results.iter().all(|r| r.passed)
+    }
+
+    pub fn failures(&self) -> Vec<&RuleResult> {
+        self.results.iter().filter(|r| !r.passed).collect()
+    }
+}
+
+impl fmt::Display for RuleReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for result in &self.results {
+            writeln!(f, "{}", result)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn can_parse_rule_set() {
+        let rules = RuleSet::parse("# policy\n\
+                                    \n\
+                                    height : Video Height == 1080\n\
+                                    profile: General Format_Profile starts_with Base Media\n").unwrap();
+
+        assert_eq!(rules.rules.len(), 2);
+        assert_eq!(rules.rules[0].name, "height");
+        assert_eq!(rules.rules[0].stream_type, MediaInfoStream::Video);
+        assert_eq!(rules.rules[0].operator, RuleOperator::Equal);
+        assert_eq!(rules.rules[1].expected, "Base Media");
+    }
+
+    #[test]
+    fn reports_line_of_invalid_rule() {
+        match RuleSet::parse("height : Video Height == 1080\nbroken : Video Height ~ 1\n") {
+            Err(MediaInfoError::RuleParseError(line)) => assert_eq!(line, 2),
+            _ => panic!("The second line should not parse."),
+        }
+    }
+
+    #[test]
+    fn can_evaluate_rules() {
+        let sample_path = PathBuf::from("samples");
+        let filename = sample_path.join("sample.mp4");
+        let mut mw = MediaInfoWrapper::new();
+        mw.open(filename.as_path()).unwrap();
+
+        let rules = RuleSet::parse("format : Video Format == AVC\n\
+                                    short  : General Duration < 10000\n\
+                                    subs   : Text Format == UTF-8\n").unwrap();
+        let report = rules.evaluate(&mw);
+
+        assert!(!report.passed());
+        assert_eq!(report.failures().len(), 1);
+        assert_eq!(report.failures()[0].rule, "subs");
+        assert_eq!(report.failures()[0].index, None);
+        mw.close();
+    }
+}
@@ -1,5 +1,16 @@
 use ffi::{MediaInfo, MediaInfoInfo, MediaInfoResult, MediaInfoError, MediaInfoStream};
-use chrono::{UTC, DateTime, NaiveDateTime};
+use language::LanguageCode;
+use subtitle::SubtitleFormat;
+use cover_art::{self, CoverArt};
+use tags::Tags;
+use loudness::{self, Loudness};
+use channel_layout::ChannelLayout;
+use immersive::ImmersiveFormat;
+use bit_rate::{BitRate, BitRateMode};
+use precision::Precision;
+use options::AnalysisDepth;
+use timecode::{FrameRate, TimeCode};
+use date::MediaDate;
 
 use std::rc::Rc;
 use std::cell::RefCell;
@@ -11,6 +22,8 @@ macro_rules! stream_struct {
             pub stream_type: MediaInfoStream,
             pub index: usize,
             pub handler: Rc<RefCell<MediaInfo>>,
+            // NOTE(erick): The depth the file was analysed with.
+            pub depth: Option<AnalysisDepth>,
         }
     }
 }
@@ -46,7 +59,7 @@ macro_rules! mediainfo_attr {
 
 macro_rules! mediainfo_date {
     ($meth_name: ident, $attr_name: tt) => (
-        pub fn $meth_name(&self) -> MediaInfoResult<DateTime<UTC>> {
+        pub fn $meth_name(&self) -> MediaInfoResult<MediaDate> {
             match self.handler() {
                 Some(rc) => {
                     self.result_to_date(rc.borrow_mut().get(self.stream_type(), self.index(), $attr_name, MediaInfoInfo::Text, MediaInfoInfo::Name))
@@ -70,6 +83,90 @@ macro_rules! mediainfo_i64 {
     )
 }
 
+macro_rules! mediainfo_u64 {
+    ($meth_name: ident, $attr_name: tt) => (
+        pub fn $meth_name(&self) -> MediaInfoResult<u64> {
+            match self.handler() {
+                Some(rc) => {
+                    self.result_to_u64(rc.borrow_mut().get(self.stream_type(), self.index(), $attr_name, MediaInfoInfo::Text, MediaInfoInfo::Name))
+                },
+                None => Err(MediaInfoError::NoDataOpenError),
+            }
+        }
+    )
+}
+
+macro_rules! mediainfo_f64 {
+    ($meth_name: ident, $attr_name: tt) => (
+        pub fn $meth_name(&self) -> MediaInfoResult<f64> {
+            match self.handler() {
+                Some(rc) => {
+                    self.result_to_f64(rc.borrow_mut().get(self.stream_type(), self.index(), $attr_name, MediaInfoInfo::Text, MediaInfoInfo::Name))
+                },
+                None => Err(MediaInfoError::NoDataOpenError),
+            }
+        }
+    )
+}
+
+macro_rules! mediainfo_decibels {
+    ($meth_name: ident, $attr_name: tt) => (
+        pub fn $meth_name(&self) -> MediaInfoResult<f64> {
+            match self.handler() {
+                Some(rc) => {
+                    self.result_to_decibels(rc.borrow_mut().get(self.stream_type(), self.index(), $attr_name, MediaInfoInfo::Text, MediaInfoInfo::Name))
+                },
+                None => Err(MediaInfoError::NoDataOpenError),
+            }
+        }
+    )
+}
+
+macro_rules! mediainfo_bit_rate {
+    ($meth_name: ident, $attr_name: tt) => (
+        pub fn $meth_name(&self) -> MediaInfoResult<BitRate> {
+            match self.handler() {
+                Some(rc) => {
+                    self.result_to_bit_rate(rc.borrow_mut().get(self.stream_type(), self.index(), $attr_name, MediaInfoInfo::Text, MediaInfoInfo::Name))
+                },
+                None => Err(MediaInfoError::NoDataOpenError),
+            }
+        }
+    )
+}
+
+// NOTE(erick): A missing mode is reported as BitRateMode::Unknown instead
+// of as an error.
+macro_rules! mediainfo_bit_rate_mode {
+    ($meth_name: ident, $attr_name: tt) => (
+        pub fn $meth_name(&self) -> BitRateMode {
+            match self.handler() {
+                Some(rc) => {
+                    match rc.borrow_mut().get(self.stream_type(), self.index(), $attr_name, MediaInfoInfo::Text, MediaInfoInfo::Name) {
+                        Ok(x) => BitRateMode::parse(&x),
+                        Err(_) => BitRateMode::Unknown,
+                    }
+                },
+                None => BitRateMode::Unknown,
+            }
+        }
+    )
+}
+
+macro_rules! mediainfo_precision {
+    ($meth_name: ident, $attr_name: tt) => (
+        pub fn $meth_name(&self) -> Precision {
+            match self.handler() {
+                Some(rc) => {
+                    let source = rc.borrow_mut().get(self.stream_type(), self.index(), concat!($attr_name, "_Source"), MediaInfoInfo::Text, MediaInfoInfo::Name);
+                    Precision::from_source(source.ok().as_deref(), self.depth)
+                },
+                None => Precision::Estimated,
+            }
+        }
+    )
+}
+
 macro_rules! mediainfo_duration {
     ($meth_name: ident, $attr_name: tt) => (
         pub fn $meth_name(&self) -> MediaInfoResult<Duration> {
@@ -83,6 +180,73 @@ macro_rules! mediainfo_duration {
     )
 }
 
+macro_rules! mediainfo_bool {
+    ($meth_name: ident, $attr_name: tt) => (
+        pub fn $meth_name(&self) -> MediaInfoResult<bool> {
+            match self.handler() {
+                Some(rc) => {
+                    self.result_to_bool(rc.borrow_mut().get(self.stream_type(), self.index(), $attr_name, MediaInfoInfo::Text, MediaInfoInfo::Name))
+                },
+                None => Err(MediaInfoError::NoDataOpenError),
+            }
+        }
+    )
+}
+
+macro_rules! mediainfo_language {
+    ($meth_name: ident, $attr_name: tt) => (
+        pub fn $meth_name(&self) -> MediaInfoResult<LanguageCode> {
+            match self.handler() {
+                Some(rc) => {
+                    self.result_to_language(rc.borrow_mut().get(self.stream_type(), self.index(), $attr_name, MediaInfoInfo::Text, MediaInfoInfo::Name))
+                },
+                None => Err(MediaInfoError::NoDataOpenError),
+            }
+        }
+    )
+}
+
+macro_rules! mediainfo_timecode {
+    ($meth_name: ident, $attr_name: tt) => (
+        pub fn $meth_name(&self) -> MediaInfoResult<TimeCode> {
+            match self.handler() {
+                Some(rc) => {
+                    self.result_to_timecode(rc.borrow_mut().get(self.stream_type(), self.index(), $attr_name, MediaInfoInfo::Text, MediaInfoInfo::Name))
+                },
+                None => Err(MediaInfoError::NoDataOpenError),
+            }
+        }
+    )
+}
+
+macro_rules! mediainfo_frame_rate {
+    ($meth_name: ident, $attr_name: tt) => (
+        pub fn $meth_name(&self) -> MediaInfoResult<FrameRate> {
+            match self.handler() {
+                Some(rc) => {
+                    let mut handle = rc.borrow_mut();
+                    let num = handle.get(self.stream_type(), self.index(), concat!($attr_name, "_Num"), MediaInfoInfo::Text, MediaInfoInfo::Name);
+                    let den = handle.get(self.stream_type(), self.index(), concat!($attr_name, "_Den"), MediaInfoInfo::Text, MediaInfoInfo::Name);
+
+                    // NOTE(erick): The exact ratio is only reported for
+                    // some containers; fall back to the decimal value.
+                    if let (Ok(num), Ok(den)) = (self.result_to_i64(num), self.result_to_i64(den)) {
+                        if num > 0 && den > 0 && num <= i64::from(u32::MAX) && den <= i64::from(u32::MAX) {
+                            if let Some(x) = FrameRate::new(num as u32, den as u32) { return Ok(x); }
+                        }
+                    }
+
+                    match FrameRate::parse(&handle.get(self.stream_type(), self.index(), $attr_name, MediaInfoInfo::Text, MediaInfoInfo::Name)?) {
+                        Some(x) => Ok(x),
+                        None => Err(MediaInfoError::NonNumericResultError),
+                    }
+                },
+                None => Err(MediaInfoError::NoDataOpenError),
+            }
+        }
+    )
+}
+
 pub struct GeneralStream {
     pub stream_type: MediaInfoStream,
     pub handler: Option<Rc<RefCell<MediaInfo>>>,
@@ -93,10 +257,40 @@ pub trait BaseStream {
     fn index(&self) -> usize;
     fn handler(&self) -> Option<&Rc<RefCell<MediaInfo>>>;
 
+    // NOTE(erick): Every field MediaInfo reports for this stream, as
+    // (name, value) pairs in MediaInfo's own order. Fields without a
+    // value are skipped.
+    fn fields(&self) -> MediaInfoResult<Vec<(String, String)>> {
+        let rc = match self.handler() {
+            Some(rc) => rc,
+            None => return Err(MediaInfoError::NoDataOpenError),
+        };
+
+        let mut handle = rc.borrow_mut();
+        let mut fields = Vec::new();
+        for i in 0..handle.count_parameters(self.stream_type(), self.index()) {
+            let name = handle.get_i(self.stream_type(), self.index(), i, MediaInfoInfo::Name);
+            let value = handle.get_i(self.stream_type(), self.index(), i, MediaInfoInfo::Text);
+
+            if let (Ok(name), Ok(value)) = (name, value) {
+                fields.push((name, value));
+            }
+        }
+
+        Ok(fields)
+    }
+
+    // NOTE(erick): Durations are milliseconds, but some of them have a
+    // fractional part (e.g. "40.000" for a frame duration).
     fn result_to_duration(&self, result: MediaInfoResult<String>) -> MediaInfoResult<Duration> {
-        match result?.parse::<u64>() {
-            Ok(x) => Ok(Duration::from_millis(x)),
-            Err(_) =>Err(MediaInfoError::NonNumericResultError),
+        let result = result?;
+        if let Ok(x) = result.parse::<u64>() {
+            return Ok(Duration::from_millis(x));
+        }
+
+        match result.parse::<f64>() {
+            Ok(x) if x >= 0.0 && x.is_finite() => Ok(Duration::from_micros((x * 1000.0).round() as u64)),
+            _ => Err(MediaInfoError::NonNumericResultError),
         }
     }
 
@@ -107,12 +301,62 @@ pub trait BaseStream {
         }
     }
 
-    fn result_to_date(&self, result: MediaInfoResult<String>) -> MediaInfoResult<DateTime<UTC>> {
-        match NaiveDateTime::parse_from_str(&result?, "UTC %Y-%m-%d %H:%M:%S") {
-            Ok(x) => Ok(DateTime::<UTC>::from_utc(x, UTC)),
+    fn result_to_u64(&self, result: MediaInfoResult<String>) -> MediaInfoResult<u64> {
+        match result?.parse::<u64>() {
+            Ok(x) => Ok(x),
+            Err(_) => Err(MediaInfoError::NonNumericResultError),
+        }
+    }
+
+    fn result_to_f64(&self, result: MediaInfoResult<String>) -> MediaInfoResult<f64> {
+        match result?.parse::<f64>() {
+            Ok(x) => Ok(x),
             Err(_) => Err(MediaInfoError::NonNumericResultError),
         }
     }
+
+    fn result_to_decibels(&self, result: MediaInfoResult<String>) -> MediaInfoResult<f64> {
+        match loudness::parse_decibels(&result?) {
+            Some(x) => Ok(x),
+            None => Err(MediaInfoError::NonNumericResultError),
+        }
+    }
+
+    fn result_to_bit_rate(&self, result: MediaInfoResult<String>) -> MediaInfoResult<BitRate> {
+        match BitRate::parse(&result?) {
+            Some(x) => Ok(x),
+            None => Err(MediaInfoError::NonNumericResultError),
+        }
+    }
+
+    fn result_to_bool(&self, result: MediaInfoResult<String>) -> MediaInfoResult<bool> {
+        match result?.as_str() {
+            "Yes" => Ok(true),
+            "No" => Ok(false),
+            _ => Err(MediaInfoError::InvalidValueError),
+        }
+    }
+
+    fn result_to_language(&self, result: MediaInfoResult<String>) -> MediaInfoResult<LanguageCode> {
+        match LanguageCode::parse(&result?) {
+            Some(x) => Ok(x),
+            None => Err(MediaInfoError::InvalidValueError),
+        }
+    }
+
+    fn result_to_timecode(&self, result: MediaInfoResult<String>) -> MediaInfoResult<TimeCode> {
+        match TimeCode::parse(&result?) {
+            Some(x) => Ok(x),
+            None => Err(MediaInfoError::InvalidValueError),
+        }
+    }
+
+    fn result_to_date(&self, result: MediaInfoResult<String>) -> MediaInfoResult<MediaDate> {
+        match MediaDate::parse(&result?) {
+            Some(x) => Ok(x),
+            None => Err(MediaInfoError::DateParseError),
+        }
+    }
 }
 
 impl BaseStream for GeneralStream {
@@ -147,7 +391,7 @@ impl GeneralStream {
     mediainfo_attr!(codec_id, "CodecID");
     mediainfo_attr!(format, "Format");
     mediainfo_attr!(format_profile, "Format_Profile");
-    mediainfo_attr!(format_info, "Format_Info");
+    mediainfo_attr!(format_info, "Format/Info");
     mediainfo_attr!(codec, "Codec");
     mediainfo_attr!(encoded_application_string, "Encoded_Application/String");
     mediainfo_attr!(encoded_application, "Encoded_Application");
@@ -162,16 +406,46 @@ impl GeneralStream {
 
     mediainfo_duration!(duration, "Duration");
 
-    mediainfo_i64!(overall_bit_rate, "OverallBitRate");
-    mediainfo_i64!(headersize, "HeaderSize");
-    mediainfo_i64!(datasize, "DataSize");
-    mediainfo_i64!(footersize, "FooterSize");
+    mediainfo_bit_rate!(overall_bit_rate, "OverallBitRate");
+    mediainfo_bit_rate!(nominal_overall_bit_rate, "OverallBitRate_Nominal");
+    mediainfo_bit_rate!(minimum_overall_bit_rate, "OverallBitRate_Minimum");
+    mediainfo_bit_rate!(maximum_overall_bit_rate, "OverallBitRate_Maximum");
+    mediainfo_bit_rate_mode!(overall_bit_rate_mode, "OverallBitRate_Mode");
+    mediainfo_u64!(file_size, "FileSize");
+    // NOTE(erick): The general stream's "StreamSize" is the size of the
+    // container itself (headers, indexes, padding...).
+    mediainfo_u64!(container_size, "StreamSize");
+    mediainfo_u64!(headersize, "HeaderSize");
+    mediainfo_u64!(datasize, "DataSize");
+    mediainfo_u64!(footersize, "FooterSize");
+    mediainfo_f64!(container_size_proportion, "StreamSize_Proportion");
 
     mediainfo_date!(mastered_date, "Mastered_Date");
     mediainfo_date!(last_modification_date, "File_Modified_Date");
     mediainfo_date!(encoded_date, "Encoded_Date");
     mediainfo_date!(tagged_date, "Tagged_Date");
 
+    mediainfo_attr!(cover_data, "Cover_Data");
+    mediainfo_attr!(cover_mime, "Cover_Mime");
+    mediainfo_attr!(cover_description, "Cover_Description");
+
+    // NOTE(erick): Empty unless the "Cover_Data" option was set to base64
+    // (see Options::cover_data) before opening the file.
+    pub fn cover_art(&self) -> Vec<CoverArt> {
+        let data = match self.cover_data() {
+            Ok(x) => x,
+            Err(_) => return Vec::new(),
+        };
+
+        cover_art::parse_covers(&data,
+                                &self.cover_mime().unwrap_or_default(),
+                                &self.cover_description().unwrap_or_default())
+    }
+
+    pub fn tags(&self) -> MediaInfoResult<Tags> {
+        Ok(Tags::from_fields(&self.fields()?))
+    }
+
     pub fn writing_application(&self) -> MediaInfoResult<String> {
        match self.encoded_application() {
             Ok(x) => Ok(x),
@@ -183,20 +457,22 @@ impl GeneralStream {
 /* VideoStream */
 impl VideoStream {
     mediainfo_attr!(stream_id, "ID");
-    mediainfo_attr!(stream_size, "StreamSize");
-    mediainfo_attr!(bit_rate, "BitRate");
-    mediainfo_attr!(nominal_bit_rate, "BitRate_Nominal");
-    mediainfo_attr!(bit_rate_mode, "BitRate_Mode");
+    mediainfo_u64!(stream_size, "StreamSize");
+    // NOTE(erick): The share of the file taken by this stream, from 0 to 1.
+    mediainfo_f64!(stream_size_proportion, "StreamSize_Proportion");
+    mediainfo_bit_rate!(bit_rate, "BitRate");
+    mediainfo_bit_rate!(nominal_bit_rate, "BitRate_Nominal");
+    mediainfo_bit_rate!(minimum_bit_rate, "BitRate_Minimum");
+    mediainfo_bit_rate!(maximum_bit_rate, "BitRate_Maximum");
+    mediainfo_bit_rate_mode!(bit_rate_mode, "BitRate_Mode");
 
     pub fn cbr(&self) -> bool {
-        match self.bit_rate_mode() {
-            Ok(x) => x == "Constant",
-            Err(_) => false
-        }
+        self.bit_rate_mode() == BitRateMode::Constant
     }
 
+    // NOTE(erick): An unknown mode is neither CBR nor VBR.
     pub fn vbr(&self) -> bool {
-        !self.cbr()
+        self.bit_rate_mode() == BitRateMode::Variable
     }
 
     mediainfo_attr!(scan_order, "ScanOrder");
@@ -214,7 +490,7 @@ impl VideoStream {
     }
 
     mediainfo_attr!(format, "Format");
-    mediainfo_attr!(format_info, "Format_Info");
+    mediainfo_attr!(format_info, "Format/Info");
     mediainfo_attr!(format_profile, "Format_Profile");
     mediainfo_attr!(format_version, "Format_Version");
     mediainfo_attr!(format_settings_cabac, "Format_Settings_CABAC");
@@ -235,7 +511,10 @@ impl VideoStream {
     mediainfo_attr!(minimum_frame_rate, "FrameRate_Minimum");
     mediainfo_attr!(maximum_frame_rate, "FrameRate_Maximum");
     mediainfo_attr!(frame_rate_mode, "FrameRate_Mode");
+    mediainfo_frame_rate!(frame_rate_ratio, "FrameRate");
+    mediainfo_timecode!(time_code_first_frame, "TimeCode_FirstFrame");
     mediainfo_attr!(display_aspect_ratio, "DisplayAspectRatio");
+    mediainfo_f64!(pixel_aspect_ratio, "PixelAspectRatio");
     mediainfo_attr!(bits_pixel_frame, "Bits-(Pixel*Frame)");
     mediainfo_duration!(duration, "Duration");
     mediainfo_i64!(bitdepth, "BitDepth");
@@ -243,6 +522,14 @@ impl VideoStream {
     mediainfo_i64!(width, "Width");
     mediainfo_i64!(height, "Height");
 
+    mediainfo_u64!(frame_count, "FrameCount");
+    mediainfo_precision!(frame_count_precision, "FrameCount");
+    mediainfo_precision!(duration_precision, "Duration");
+
+    pub fn frame_duration(&self) -> MediaInfoResult<Duration> {
+        Ok(self.frame_rate_ratio()?.frame_duration())
+    }
+
     pub fn frame_size(&self) -> MediaInfoResult<String> {
         let height = self.height()?;
         let width = self.width()?;
@@ -252,18 +539,61 @@ impl VideoStream {
 
     mediainfo_date!(encoded_date, "Encoded_Date");
     mediainfo_date!(tagged_date, "Tagged_Date");
-    mediainfo_date!(standard, "Standard");
+    mediainfo_attr!(standard, "Standard");
 }
 
 /* AudioStream */
 impl AudioStream {
     mediainfo_attr!(stream_id, "ID");
     mediainfo_duration!(duration, "Duration");
-    mediainfo_attr!(sampling_count, "SamplingCount");
+    mediainfo_precision!(duration_precision, "Duration");
+    mediainfo_u64!(sample_count, "SamplingCount");
+    mediainfo_precision!(sample_count_precision, "SamplingCount");
     mediainfo_i64!(sampling_rate, "SamplingRate");
-    mediainfo_attr!(stream_size, "StreamSize");
-    mediainfo_attr!(bit_rate, "BitRate");
-    mediainfo_attr!(bit_rate_mode, "BitRate_Mode");
+
+    mediainfo_u64!(frame_count, "FrameCount");
+    mediainfo_precision!(frame_count_precision, "FrameCount");
+    // NOTE(erick): Not always a whole number (e.g. "1152.000", or
+    // fractional for some codecs).
+    mediainfo_f64!(samples_per_frame, "SamplesPerFrame");
+
+    pub fn sample_duration(&self) -> MediaInfoResult<Duration> {
+        let rate = self.sampling_rate()?;
+        if rate <= 0 { return Err(MediaInfoError::InvalidValueError); }
+
+        Ok(Duration::from_nanos(1_000_000_000 / rate as u64))
+    }
+
+    // NOTE(erick): The duration of a codec frame (e.g. 1024 samples for
+    // AAC), not of a single sample.
+    pub fn frame_duration(&self) -> MediaInfoResult<Duration> {
+        let rate = self.sampling_rate()?;
+        if rate <= 0 { return Err(MediaInfoError::InvalidValueError); }
+
+        let samples_per_frame = self.samples_per_frame()?;
+        if samples_per_frame < 0.0 || !samples_per_frame.is_finite() { return Err(MediaInfoError::InvalidValueError); }
+
+        Ok(Duration::from_secs_f64(samples_per_frame / rate as f64))
+    }
+
+    mediainfo_u64!(stream_size, "StreamSize");
+    // NOTE(erick): The share of the file taken by this stream, from 0 to 1.
+    mediainfo_f64!(stream_size_proportion, "StreamSize_Proportion");
+    mediainfo_bit_rate!(bit_rate, "BitRate");
+    mediainfo_bit_rate!(nominal_bit_rate, "BitRate_Nominal");
+    mediainfo_bit_rate!(minimum_bit_rate, "BitRate_Minimum");
+    mediainfo_bit_rate!(maximum_bit_rate, "BitRate_Maximum");
+    mediainfo_bit_rate_mode!(bit_rate_mode, "BitRate_Mode");
+
+    pub fn cbr(&self) -> bool {
+        self.bit_rate_mode() == BitRateMode::Constant
+    }
+
+    // NOTE(erick): An unknown mode is neither CBR nor VBR.
+    pub fn vbr(&self) -> bool {
+        self.bit_rate_mode() == BitRateMode::Variable
+    }
+
     mediainfo_attr!(interleave_duration, "Interleave_Duration");
     mediainfo_i64!(resolution, "Resolution");
     mediainfo_attr!(format, "Format");
@@ -273,24 +603,89 @@ impl AudioStream {
     mediainfo_attr!(format_settings_sbr, "Format_Settings_SBR");
     mediainfo_attr!(format_settings_endianness, "Format_Settings_Endianness");
     mediainfo_attr!(format_settings_sign, "Format_Settings_Sign");
+    mediainfo_attr!(format_commercial, "Format_Commercial");
+    mediainfo_attr!(format_additional_features, "Format_AdditionalFeatures");
     mediainfo_attr!(codec_id, "CodecID");
     mediainfo_attr!(codec_info, "CodecID/Info");
     mediainfo_attr!(codec, "Codec");
     mediainfo_attr!(codec_id_hint, "CodecID/Hint");
     mediainfo_attr!(channel_positions, "ChannelPositions");
     mediainfo_i64!(channels, "Channel(s)");
+    mediainfo_attr!(title, "Title");
+    mediainfo_language!(language, "Language");
+    mediainfo_bool!(default, "Default");
+    mediainfo_bool!(forced, "Forced");
+
+    mediainfo_decibels!(replay_gain, "ReplayGain_Gain");
+    mediainfo_f64!(replay_gain_peak, "ReplayGain_Peak");
+    mediainfo_decibels!(dialnorm, "dialnorm");
+    mediainfo_decibels!(dialnorm_average, "dialnorm_Average");
+    mediainfo_decibels!(dialnorm_minimum, "dialnorm_Minimum");
+    mediainfo_decibels!(dialnorm_maximum, "dialnorm_Maximum");
+    // NOTE(erick): AC-3/E-AC-3 bitstream codes, see ATSC A/52: the
+    // service type (bsmod), the channel mode (acmod) and whether 2.0
+    // audio is Dolby Surround encoded (dsurmod).
+    mediainfo_i64!(bsmod, "bsmod");
+    mediainfo_i64!(acmod, "acmod");
+    mediainfo_i64!(dsurmod, "dsurmod");
+
+    pub fn loudness(&self) -> Loudness {
+        Loudness {
+            replay_gain: self.replay_gain().ok(),
+            replay_gain_peak: self.replay_gain_peak().ok(),
+            dialnorm: self.dialnorm().ok(),
+            dialnorm_minimum: self.dialnorm_minimum().ok(),
+            dialnorm_maximum: self.dialnorm_maximum().ok(),
+            dialnorm_average: self.dialnorm_average().ok(),
+        }
+    }
+
+    pub fn has_loudness_metadata(&self) -> bool {
+        !self.loudness().is_empty()
+    }
+
+    pub fn immersive(&self) -> Option<ImmersiveFormat> {
+        let format = self.format().ok()?;
+        ImmersiveFormat::detect(&format,
+                                &self.format_commercial().unwrap_or_default(),
+                                &self.format_additional_features().unwrap_or_default())
+    }
+
+    // NOTE(erick): Newer versions of MediaInfo report the layout in
+    // "ChannelLayout", older ones only in "ChannelPositions".
+    pub fn channel_layout(&self) -> MediaInfoResult<ChannelLayout> {
+        let layout = match self.handler() {
+            Some(rc) => rc.borrow_mut().get(self.stream_type(), self.index(), "ChannelLayout", MediaInfoInfo::Text, MediaInfoInfo::Name),
+            None => return Err(MediaInfoError::NoDataOpenError),
+        };
+
+        if let Some(x) = layout.ok().and_then(|x| ChannelLayout::parse(&x)) {
+            return Ok(x);
+        }
+
+        match ChannelLayout::parse_positions(&self.channel_positions()?) {
+            Some(x) => Ok(x),
+            None => Err(MediaInfoError::InvalidValueError),
+        }
+    }
 
+    // NOTE(erick): Without a (known) layout, two channels can only be
+    // assumed to be stereo.
     pub fn stereo(&self) -> bool {
-        match self.channels() {
-            Ok(x) => x == 2,
-            Err(_) => false
+        match self.channel_layout() {
+            Ok(ref x) if !x.has_unknown_positions() => x.is_stereo(),
+            _ => self.channels().map(|x| x == 2).unwrap_or(false),
         }
     }
 
+    pub fn dual_mono(&self) -> bool {
+        self.channel_layout().map(|x| x.is_dual_mono()).unwrap_or(false)
+    }
+
     pub fn mono(&self) -> bool {
-        match self.channels() {
-            Ok(x) => x == 1,
-            Err(_) => false
+        match self.channel_layout() {
+            Ok(x) => x.is("1.0"),
+            Err(_) => self.channels().map(|x| x == 1).unwrap_or(false),
         }
     }
 
@@ -303,6 +698,8 @@ impl ImageStream {
     mediainfo_attr!(format, "Format");
     mediainfo_i64!(width, "Width");
     mediainfo_i64!(height, "Height");
+    mediainfo_u64!(stream_size, "StreamSize");
+    mediainfo_f64!(stream_size_proportion, "StreamSize_Proportion");
 
     pub fn frame_size(&self) -> MediaInfoResult<String> {
         let height = self.height()?;
@@ -315,9 +712,48 @@ impl ImageStream {
 /* TextStream */
 impl TextStream {
     mediainfo_attr!(stream_id, "ID");
+    mediainfo_u64!(stream_size, "StreamSize");
+    mediainfo_f64!(stream_size_proportion, "StreamSize_Proportion");
     mediainfo_attr!(format, "Format");
     mediainfo_attr!(codec_id, "CodecID");
     mediainfo_attr!(codec_info, "CodecID/Info");
+    mediainfo_attr!(title, "Title");
+    mediainfo_language!(language, "Language");
+    mediainfo_bool!(default, "Default");
+    mediainfo_bool!(forced, "Forced");
+    mediainfo_i64!(element_count, "ElementCount");
+    mediainfo_duration!(duration, "Duration");
+    mediainfo_i64!(delay, "Delay");
+    mediainfo_attr!(muxing_mode, "MuxingMode");
+
+    pub fn subtitle_format(&self) -> MediaInfoResult<SubtitleFormat> {
+        Ok(SubtitleFormat::from_format(&self.format()?))
+    }
+
+    pub fn bitmap(&self) -> bool {
+        match self.subtitle_format() {
+            Ok(x) => x.is_bitmap(),
+            Err(_) => false
+        }
+    }
+
+    // NOTE(erick): CEA-608/708 captions carried inside the video
+    // elementary stream (e.g. ATSC A/53 user data or MXF ancillary data)
+    // instead of in a track of their own.
+    pub fn embedded_in_video(&self) -> bool {
+        let closed_caption = match self.subtitle_format() {
+            Ok(x) => x.is_closed_caption(),
+            Err(_) => false
+        };
+        if !closed_caption { return false; }
+
+        match self.muxing_mode() {
+            Ok(x) => ["A/53", "SCTE 20", "SCTE 128", "DTVCC", "DVD-Video", "Ancillary data"]
+                .iter()
+                .any(|m| x.contains(m)),
+            Err(_) => false
+        }
+    }
 }
 
 /* OtherStream */
@@ -325,6 +761,8 @@ impl OtherStream {
     mediainfo_attr!(stream_id, "ID");
     mediainfo_attr!(other_type, "Type");
     mediainfo_attr!(timecode, "TimeCode_FirstFrame");
+    mediainfo_timecode!(time_code_first_frame, "TimeCode_FirstFrame");
+    mediainfo_frame_rate!(frame_rate_ratio, "FrameRate");
 }
 
 /* MenuStream */
@@ -334,3 +772,7 @@ impl MenuStream {
     mediainfo_date!(tagged_date, "Tagged_Date");
     mediainfo_i64!(delay, "Delay");
 }
+
+// NOTE(erick): Accessors for every other parameter in MediaInfo's catalog,
+// generated by examples/generate_accessors.rs.
+include!("generated_streams.rs");
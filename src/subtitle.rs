@@ -0,0 +1,61 @@
+// Subtitle formats, as reported in the "Format" field of text streams.
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubtitleFormat {
+    Srt,
+    Ass,
+    Pgs,
+    VobSub,
+    Ttml,
+    WebVtt,
+    Cea608,
+    Cea708,
+    Other(String),
+}
+
+impl SubtitleFormat {
+    pub fn from_format(format: &str) -> SubtitleFormat {
+        match format {
+            // NOTE(erick): Matroska and MP4 report SubRip tracks by their
+            // encoding, while standalone files are reported as "SubRip".
+            "SubRip" | "UTF-8" => SubtitleFormat::Srt,
+            "ASS" | "SSA" => SubtitleFormat::Ass,
+            "PGS" => SubtitleFormat::Pgs,
+            // NOTE(erick): DVD subpictures in MPEG-PS are reported by
+            // their encoding (Run-Length Encoding).
+            "VobSub" | "RLE" => SubtitleFormat::VobSub,
+            "TTML" => SubtitleFormat::Ttml,
+            "WebVTT" => SubtitleFormat::WebVtt,
+            "EIA-608" | "CEA-608" => SubtitleFormat::Cea608,
+            "EIA-708" | "CEA-708" => SubtitleFormat::Cea708,
+            _ => SubtitleFormat::Other(format.to_string()),
+        }
+    }
+
+    pub fn is_bitmap(&self) -> bool {
+        match *self {
+            SubtitleFormat::Pgs | SubtitleFormat::VobSub => true,
+            SubtitleFormat::Other(ref f) => f == "DVB Subtitle",
+            _ => false,
+        }
+    }
+
+    pub fn is_closed_caption(&self) -> bool {
+        matches!(*self, SubtitleFormat::Cea608 | SubtitleFormat::Cea708)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_identify_subtitle_formats() {
+        assert_eq!(SubtitleFormat::from_format("UTF-8"), SubtitleFormat::Srt);
+        assert_eq!(SubtitleFormat::from_format("EIA-608"), SubtitleFormat::Cea608);
+        assert!(SubtitleFormat::from_format("PGS").is_bitmap());
+        assert!(!SubtitleFormat::from_format("WebVTT").is_bitmap());
+        assert_eq!(SubtitleFormat::from_format("Timed Text"),
+                   SubtitleFormat::Other("Timed Text".to_string()));
+    }
+}
@@ -0,0 +1,137 @@
+// Music tags (ID3, Vorbis comments, iTunes atoms...) as MediaInfo reports
+// them in the general stream. MediaInfo normalizes the common tags to its
+// own field names, but keeps unknown ones (such as the MusicBrainz IDs)
+// under their original names, which vary between tag formats.
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Tags {
+    pub track_position: Option<u32>,
+    pub track_total: Option<u32>,
+    pub disc_position: Option<u32>,
+    pub disc_total: Option<u32>,
+    pub album_artist: Option<String>,
+    pub composer: Option<String>,
+    pub isrc: Option<String>,
+    pub label: Option<String>,
+    pub lyrics: Option<String>,
+    pub comment: Option<String>,
+    pub compilation: Option<bool>,
+    pub bpm: Option<f64>,
+    // NOTE(erick): In dB.
+    pub album_replay_gain: Option<f64>,
+    pub album_replay_gain_peak: Option<f64>,
+    pub musicbrainz_track_id: Option<String>,
+    pub musicbrainz_album_id: Option<String>,
+    pub musicbrainz_artist_id: Option<String>,
+    pub musicbrainz_album_artist_id: Option<String>,
+    pub musicbrainz_release_group_id: Option<String>,
+}
+
+// NOTE(erick): Positions may be reported as "3/12" by some tag formats.
+fn parse_position(value: &str) -> (Option<u32>, Option<u32>) {
+    let mut parts = value.splitn(2, '/');
+    let position = parts.next().and_then(|x| x.trim().parse().ok());
+    let total = parts.next().and_then(|x| x.trim().parse().ok());
+
+    (position, total)
+}
+
+// NOTE(erick): Accepts "-6.48", "-6.48 dB" and "120 BPM".
+fn parse_number(value: &str) -> Option<f64> {
+    value.split_whitespace().next().and_then(|x| x.parse().ok())
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_lowercase().as_str() {
+        "yes" | "1" | "true" => Some(true),
+        "no" | "0" | "false" => Some(false),
+        _ => None,
+    }
+}
+
+// NOTE(erick): "MusicBrainz Album Id" (ID3, iTunes) and
+// "MUSICBRAINZ_ALBUMID" (Vorbis) both become "musicbrainzalbumid".
+fn normalize(name: &str) -> String {
+    name.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+impl Tags {
+    pub fn from_fields(fields: &[(String, String)]) -> Tags {
+        let mut tags = Tags::default();
+
+        for (name, value) in fields.iter() {
+            let value = value.trim();
+            if value.is_empty() { continue; }
+            let text = Some(value.to_string());
+
+            match name.as_str() {
+                "Track/Position" => {
+                    let (position, total) = parse_position(value);
+                    tags.track_position = position;
+                    tags.track_total = tags.track_total.or(total);
+                },
+                "Track/Position_Total" => tags.track_total = value.parse().ok(),
+                "Part/Position" => {
+                    let (position, total) = parse_position(value);
+                    tags.disc_position = position;
+                    tags.disc_total = tags.disc_total.or(total);
+                },
+                "Part/Position_Total" => tags.disc_total = value.parse().ok(),
+                "Album/Performer" => tags.album_artist = text,
+                "Composer" => tags.composer = text,
+                "ISRC" => tags.isrc = text,
+                "Label" | "Publisher" => tags.label = tags.label.take().or(text),
+                "Lyrics" => tags.lyrics = text,
+                "Comment" => tags.comment = text,
+                "Compilation" => tags.compilation = parse_flag(value),
+                "BPM" => tags.bpm = parse_number(value),
+                "Album_ReplayGain_Gain" => tags.album_replay_gain = parse_number(value),
+                "Album_ReplayGain_Peak" => tags.album_replay_gain_peak = parse_number(value),
+                _ => match normalize(name).as_str() {
+                    "musicbrainztrackid" | "musicbrainzreleasetrackid" =>
+                        tags.musicbrainz_track_id = tags.musicbrainz_track_id.take().or(text),
+                    "musicbrainzalbumid" => tags.musicbrainz_album_id = text,
+                    "musicbrainzartistid" => tags.musicbrainz_artist_id = text,
+                    "musicbrainzalbumartistid" => tags.musicbrainz_album_artist_id = text,
+                    "musicbrainzreleasegroupid" => tags.musicbrainz_release_group_id = text,
+                    _ => {},
+                },
+            }
+        }
+
+        tags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fields(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs.iter().map(|&(n, v)| (n.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn can_build_tags() {
+        let tags = Tags::from_fields(&fields(&[("Track/Position", "3/12"),
+                                               ("Part/Position", "1"),
+                                               ("Part/Position_Total", "2"),
+                                               ("Album/Performer", "Various Artists"),
+                                               ("Compilation", "Yes"),
+                                               ("BPM", "128"),
+                                               ("Album_ReplayGain_Gain", "-6.48 dB"),
+                                               ("MUSICBRAINZ_ALBUMID", "b84ee12a-09ef-421b-82de-0441a926375b"),
+                                               ("MusicBrainz Artist Id", "a74b1b7f-71a5-4011-9441-d0b5e4122711"),
+                                               ("Comment", "")]));
+
+        assert_eq!((tags.track_position, tags.track_total), (Some(3), Some(12)));
+        assert_eq!((tags.disc_position, tags.disc_total), (Some(1), Some(2)));
+        assert_eq!(tags.album_artist, Some("Various Artists".to_string()));
+        assert_eq!(tags.compilation, Some(true));
+        assert_eq!(tags.bpm, Some(128.0));
+        assert_eq!(tags.album_replay_gain, Some(-6.48));
+        assert_eq!(tags.musicbrainz_album_id, Some("b84ee12a-09ef-421b-82de-0441a926375b".to_string()));
+        assert_eq!(tags.musicbrainz_artist_id, Some("a74b1b7f-71a5-4011-9441-d0b5e4122711".to_string()));
+        assert_eq!(tags.comment, None);
+    }
+}
@@ -0,0 +1,271 @@
+// SMPTE timecodes (HH:MM:SS:FF) and the frame rates needed to do any
+// arithmetic with them. Drop-frame timecodes use ';' as the frames
+// separator (HH:MM:SS;FF), as MediaInfo reports them.
+
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+// NOTE(erick): The fields are private so that every FrameRate has a non
+// zero numerator, denominator and timecode base, which the timecode
+// arithmetic divides by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameRate {
+    numerator: u32,
+    denominator: u32,
+}
+
+impl FrameRate {
+    // NOTE(erick): Rates below 0.5 fps (whose timecode base would be 0)
+    // are rejected as well.
+    pub fn new(numerator: u32, denominator: u32) -> Option<FrameRate> {
+        if numerator == 0 || denominator == 0 { return None; }
+
+        let rate = FrameRate { numerator, denominator };
+        if rate.timecode_base() == 0 { return None; }
+
+        Some(rate)
+    }
+
+    pub fn numerator(&self) -> u32 {
+        self.numerator
+    }
+
+    pub fn denominator(&self) -> u32 {
+        self.denominator
+    }
+
+    // NOTE(erick): MediaInfo reports frame rates as decimals (e.g.
+    // "29.970"). The NTSC family of rates is mapped back to its exact
+    // x/1001 ratio, everything else is kept with millisecond precision.
+    pub fn parse(rate: &str) -> Option<FrameRate> {
+        let value = rate.trim().parse::<f64>().ok()?;
+        if value <= 0.0 || !value.is_finite() { return None; }
+
+        let ntsc = (value * 1001.0 / 1000.0).round();
+        if (value - ntsc * 1000.0 / 1001.0).abs() < 0.001 && (value - ntsc).abs() > 0.001 {
+            return FrameRate::new(ntsc as u32 * 1000, 1001);
+        }
+
+        FrameRate::new((value * 1000.0).round() as u32, 1000).map(|x| x.reduced())
+    }
+
+    pub fn as_f64(&self) -> f64 {
+        f64::from(self.numerator) / f64::from(self.denominator)
+    }
+
+    // NOTE(erick): The integer frame rate timecodes count at, e.g. 30
+    // for 29.97.
+    pub fn timecode_base(&self) -> u32 {
+        self.as_f64().round() as u32
+    }
+
+    // NOTE(erick): `u32::is_multiple_of` would need Rust 1.87.
+    #[allow(unknown_lints, clippy::manual_is_multiple_of)]
+    pub fn supports_drop_frame(&self) -> bool {
+        self.denominator == 1001 && self.timecode_base() % 30 == 0
+    }
+
+    pub fn frame_duration(&self) -> Duration {
+        let nanos = 1_000_000_000u64 * u64::from(self.denominator) / u64::from(self.numerator);
+        Duration::from_nanos(nanos)
+    }
+
+    fn reduced(self) -> FrameRate {
+        let (mut a, mut b) = (self.numerator, self.denominator);
+        while b != 0 {
+            let t = a % b;
+            a = b;
+            b = t;
+        }
+
+        if a == 0 { return self; }
+        FrameRate { numerator: self.numerator / a, denominator: self.denominator / a }
+    }
+}
+
+impl fmt::Display for FrameRate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:.3}", self.as_f64())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeCode {
+    pub hours: u32,
+    pub minutes: u32,
+    pub seconds: u32,
+    pub frames: u32,
+    pub drop_frame: bool,
+}
+
+impl TimeCode {
+    pub fn new(hours: u32, minutes: u32, seconds: u32, frames: u32, drop_frame: bool) -> TimeCode {
+        TimeCode { hours, minutes, seconds, frames, drop_frame }
+    }
+
+    pub fn parse(timecode: &str) -> Option<TimeCode> {
+        let timecode = timecode.trim();
+        let drop_frame = timecode.contains(';');
+
+        let parts: Vec<&str> = timecode.split([':', ';']).collect();
+        if parts.len() != 4 { return None; }
+
+        let mut values = [0u32; 4];
+        for (value, part) in values.iter_mut().zip(parts.iter()) {
+            *value = part.parse::<u32>().ok()?;
+        }
+
+        if values[1] >= 60 || values[2] >= 60 { return None; }
+
+        Some(TimeCode::new(values[0], values[1], values[2], values[3], drop_frame))
+    }
+
+    // NOTE(erick): Drop-frame timecodes skip the first frame numbers of
+    // every minute, except every tenth minute, so that the timecode
+    // stays in sync with the wall clock at x/1001 rates. At rates that do
+    // not support drop-frame, timecodes are counted as non drop-frame.
+    pub fn to_frames(self, rate: &FrameRate) -> u64 {
+        let base = u64::from(rate.timecode_base());
+        let total_seconds = u64::from(self.hours) * 3600 +
+                            u64::from(self.minutes) * 60 +
+                            u64::from(self.seconds);
+        let frames = total_seconds * base + u64::from(self.frames);
+
+        if !self.drop_frame || !rate.supports_drop_frame() { return frames; }
+
+        let dropped = base / 15;
+        let total_minutes = u64::from(self.hours) * 60 + u64::from(self.minutes);
+        frames - dropped * (total_minutes - total_minutes / 10)
+    }
+
+    pub fn from_frames(frames: u64, rate: &FrameRate, drop_frame: bool) -> TimeCode {
+        let base = u64::from(rate.timecode_base());
+        let drop_frame = drop_frame && rate.supports_drop_frame();
+        let mut frames = frames;
+
+        if drop_frame {
+            let dropped = base / 15;
+            let frames_per_10_minutes = base * 600 - dropped * 9;
+            let frames_per_minute = base * 60 - dropped;
+
+            let tens = frames / frames_per_10_minutes;
+            let rest = frames % frames_per_10_minutes;
+            frames += dropped * 9 * tens;
+            if rest > dropped {
+                frames += dropped * ((rest - dropped) / frames_per_minute);
+            }
+        }
+
+        TimeCode {
+            hours: (frames / (base * 3600)) as u32,
+            minutes: ((frames / (base * 60)) % 60) as u32,
+            seconds: ((frames / base) % 60) as u32,
+            frames: (frames % base) as u32,
+            drop_frame,
+        }
+    }
+
+    // NOTE(erick): The intermediate products are computed in u128, since
+    // they overflow u64 after a few days worth of frames.
+    pub fn to_duration(self, rate: &FrameRate) -> Duration {
+        let nanos = u128::from(self.to_frames(rate)) * 1_000_000_000 *
+                    u128::from(rate.denominator) / u128::from(rate.numerator);
+        Duration::new((nanos / 1_000_000_000) as u64, (nanos % 1_000_000_000) as u32)
+    }
+
+    // NOTE(erick): Rounds down to the frame that contains `duration`.
+    pub fn from_duration(duration: Duration, rate: &FrameRate, drop_frame: bool) -> TimeCode {
+        let frames = duration.as_nanos() * u128::from(rate.numerator) /
+                     (1_000_000_000 * u128::from(rate.denominator));
+        TimeCode::from_frames(frames as u64, rate, drop_frame)
+    }
+
+    // NOTE(erick): Saturates at 00:00:00:00 when going backwards.
+    pub fn add_frames(self, frames: i64, rate: &FrameRate) -> TimeCode {
+        let current = self.to_frames(rate).min(i64::MAX as u64) as i64;
+        let result = current.saturating_add(frames).max(0) as u64;
+        TimeCode::from_frames(result, rate, self.drop_frame)
+    }
+}
+
+impl FromStr for TimeCode {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<TimeCode, ()> {
+        TimeCode::parse(s).ok_or(())
+    }
+}
+
+impl fmt::Display for TimeCode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let separator = if self.drop_frame { ';' } else { ':' };
+        write!(f, "{:02}:{:02}:{:02}{}{:02}",
+               self.hours, self.minutes, self.seconds, separator, self.frames)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_parse_frame_rates() {
+        assert_eq!(FrameRate::parse("29.970"), FrameRate::new(30000, 1001));
+        assert_eq!(FrameRate::parse("23.976"), FrameRate::new(24000, 1001));
+        assert_eq!(FrameRate::parse("25.000"), FrameRate::new(25, 1));
+        assert_eq!(FrameRate::parse("12.5"), FrameRate::new(25, 2));
+        assert!(FrameRate::new(60000, 1001).unwrap().supports_drop_frame());
+        assert!(!FrameRate::new(24000, 1001).unwrap().supports_drop_frame());
+
+        assert_eq!(FrameRate::parse("0.4"), None);
+        assert_eq!(FrameRate::parse("0.0004"), None);
+        assert_eq!(FrameRate::new(0, 1), None);
+        assert_eq!(FrameRate::new(25, 0), None);
+    }
+
+    #[test]
+    fn can_convert_non_drop_frame_timecodes() {
+        let rate = FrameRate::new(25, 1).unwrap();
+        let tc = TimeCode::parse("01:00:00:00").unwrap();
+
+        assert_eq!(tc.to_frames(&rate), 90000);
+        assert_eq!(tc.to_duration(&rate), Duration::from_secs(3600));
+        assert_eq!(tc.add_frames(-1, &rate).to_string(), "00:59:59:24");
+        assert_eq!(tc.add_frames(i64::MIN, &rate).to_string(), "00:00:00:00");
+        // NOTE(erick): Must not overflow.
+        tc.add_frames(i64::MAX, &rate);
+        assert_eq!(TimeCode::from_duration(Duration::from_millis(1500), &rate, false).to_string(),
+                   "00:00:01:12");
+    }
+
+    #[test]
+    fn can_convert_drop_frame_timecodes() {
+        let rate = FrameRate::new(30000, 1001).unwrap();
+        let tc = TimeCode::parse("00:01:00;02").unwrap();
+
+        assert!(tc.drop_frame);
+        assert_eq!(tc.to_frames(&rate), 1800);
+        assert_eq!(tc.add_frames(-1, &rate).to_string(), "00:00:59;29");
+        assert_eq!(TimeCode::from_frames(17982, &rate, true).to_string(), "00:10:00;00");
+        assert_eq!(TimeCode::parse("01:00:00;00").unwrap().to_frames(&rate), 107892);
+
+        let long = Duration::from_secs(100 * 3600);
+        assert_eq!(TimeCode::from_duration(long, &rate, true).to_frames(&rate), 10789210);
+        assert_eq!(TimeCode::from_frames(10789210, &rate, true).to_duration(&rate).as_secs(), 359999);
+    }
+
+    #[test]
+    fn ignores_drop_frame_at_other_rates() {
+        for &(numerator, denominator) in &[(25, 1), (24000, 1001)] {
+            let rate = FrameRate::new(numerator, denominator).unwrap();
+            let tc = TimeCode::parse("00:10:00;00").unwrap();
+            let base = u64::from(rate.timecode_base());
+
+            assert_eq!(tc.to_frames(&rate), 600 * base);
+            let back = TimeCode::from_frames(600 * base, &rate, true);
+            assert!(!back.drop_frame);
+            assert_eq!(back.to_string(), "00:10:00:00");
+        }
+    }
+}